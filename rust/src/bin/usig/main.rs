@@ -6,10 +6,43 @@ use comfy_table::Table;
 use tracing::*;
 
 use crate::parser::*;
-use clap::{Parser, ValueEnum};
+use clap::{error::ErrorKind, CommandFactory, Parser, Subcommand, ValueEnum};
+use usig::FrontierPolicy;
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
+#[command(args_conflicts_with_subcommands = true, arg_required_else_help = true)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+    #[command(flatten)]
+    search: Option<Args>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Computes the distance between every pair of nodes
+    Apsp(ApspArgs),
+}
+
+#[derive(clap::Args, Debug)]
+struct ApspArgs {
+    input: PathBuf,
+    output: PathBuf,
+    #[arg(short = 'a', long, value_enum, default_value = "floyd-warshall")]
+    algorithm: ApspAlgorithms,
+}
+
+#[derive(ValueEnum, Debug, Clone)]
+#[value()]
+enum ApspAlgorithms {
+    #[value()]
+    FloydWarshall,
+    #[value()]
+    Johnson,
+}
+
+#[derive(clap::Args, Debug)]
 struct Args {
     input: PathBuf,
     output: PathBuf,
@@ -17,6 +50,33 @@ struct Args {
     ending_node: usize,
     #[arg(short = 'a', long, value_enum, default_value = "bfs")]
     algorithm: Algorithms,
+    /// Seed for the algorithms that make random choices, a random one is used if missing
+    #[arg(short = 's', long)]
+    seed: Option<u64>,
+    /// File with the heuristic estimate of each node, one `node estimate` pair per line
+    #[arg(
+        long,
+        required_if_eq_any([
+            ("algorithm", "astar"),
+            ("algorithm", "ida-star"),
+            ("algorithm", "greedy"),
+            ("algorithm", "beam"),
+        ])
+    )]
+    heuristic: Option<PathBuf>,
+    /// Depth limit of the depth-limited searches, defaults to the number of nodes for iddfs
+    #[arg(short = 'l', long, required_if_eq("algorithm", "dls"))]
+    limit: Option<usize>,
+    /// Number of nodes kept on each level of the beam search
+    #[arg(short = 'w', long, required_if_eq("algorithm", "beam"))]
+    width: Option<usize>,
+    /// Prints the k shortest loopless paths, one per row, instead of running a search
+    #[arg(short = 'k', long)]
+    k: Option<usize>,
+    /// Prints the state of the search after every expansion, one row per iteration, for
+    /// bfs, dfs and mod-bfs
+    #[arg(long, conflicts_with = "k")]
+    trace: bool,
 }
 
 #[derive(ValueEnum, Debug, Clone)]
@@ -25,24 +85,179 @@ enum Algorithms {
     #[value()]
     Bfs,
     #[value()]
+    ModBfs,
+    #[value()]
+    BidirectionalBfs,
+    #[value()]
     Dfs,
+    #[value()]
+    Dls,
+    #[value()]
+    Iddfs,
+    #[value()]
+    Ucs,
+    #[value()]
+    Astar,
+    #[value()]
+    IdaStar,
+    #[value()]
+    Greedy,
+    #[value()]
+    Beam,
 }
 
 #[instrument]
 fn main() {
     tracing_subscriber::fmt::init();
-    let args = Args::parse();
-    debug!(?args);
+    let cli = Cli::parse();
+    debug!(?cli);
+    match cli.command {
+        Some(Command::Apsp(args)) => apsp(args),
+        None => search(
+            cli.search
+                .expect("search arguments are required without a command"),
+        ),
+    }
+}
+
+fn apsp(args: ApspArgs) {
+    let input = std::fs::read_to_string(args.input).unwrap();
+    let (_, graph) = parse_graph(&input).unwrap();
+    let matrix = match args.algorithm {
+        ApspAlgorithms::FloydWarshall => graph.floyd_warshall(),
+        ApspAlgorithms::Johnson => graph.johnson(),
+    };
+    let matrix = match matrix {
+        Ok(matrix) => matrix,
+        Err(cycle) => Cli::command()
+            .error(
+                ErrorKind::InvalidValue,
+                format!("the distances are not defined, the graph has a {cycle}"),
+            )
+            .exit(),
+    };
+    let mut nodes = matrix.nodes().iter().copied().collect::<Vec<_>>();
+    nodes.sort();
+    let output = std::fs::File::create(args.output).unwrap();
+    let mut writer = std::io::BufWriter::new(output);
+    let mut header = vec!["Distance (from \\ to)".to_string()];
+    header.extend(nodes.iter().map(|node| node.to_string()));
+    let mut distances = Table::new();
+    distances.set_header(header.clone());
+    let mut next_hops = Table::new();
+    header[0] = "Next Hop (from \\ to)".to_string();
+    next_hops.set_header(header);
+    for &from in &nodes {
+        let mut distance_row = vec![from.to_string()];
+        let mut next_hop_row = vec![from.to_string()];
+        for &to in &nodes {
+            distance_row.push(
+                matrix
+                    .distance(from, to)
+                    .map_or_else(String::new, |f| f.to_string()),
+            );
+            next_hop_row.push(
+                matrix
+                    .next_hop(from, to)
+                    .map_or_else(String::new, |node| node.to_string()),
+            );
+        }
+        distances.add_row(distance_row);
+        next_hops.add_row(next_hop_row);
+    }
+    writer
+        .write_all(format!("{distances}\n{next_hops}").as_bytes())
+        .unwrap();
+}
+
+fn search(args: Args) {
     let input = std::fs::read_to_string(args.input).unwrap();
     let (_, graph) = parse_graph(&input).unwrap();
-    let result = match args.algorithm {
-        Algorithms::Bfs => graph.bfs(args.starting_node, args.ending_node),
-        Algorithms::Dfs => graph.dfs(args.starting_node, args.ending_node),
+    let seed = args.seed.unwrap_or_else(rand::random);
+    let heuristic = args
+        .heuristic
+        .as_ref()
+        .map(|path| {
+            let input = std::fs::read_to_string(path).unwrap();
+            match parse_heuristic(&input) {
+                Ok(heuristic) => heuristic,
+                Err(err) => Cli::command()
+                    .error(
+                        ErrorKind::InvalidValue,
+                        format!("malformed heuristic file {}: {err}", path.display()),
+                    )
+                    .exit(),
+            }
+        })
+        .unwrap_or_default();
+    if let Some(k) = args.k {
+        let paths = graph.k_shortest_paths(args.starting_node, args.ending_node, k);
+        let output = std::fs::File::create(args.output).unwrap();
+        let mut writer = std::io::BufWriter::new(output);
+        let mut table = Table::new();
+        table.set_header(vec!["Rank", "Path", "Distance"]);
+        for (rank, (path, distance)) in paths.iter().enumerate() {
+            table.add_row(vec![
+                (rank + 1).to_string(),
+                path.iter()
+                    .map(|node| node.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" -> "),
+                distance.to_string(),
+            ]);
+        }
+        writer.write_all(format!("{table}").as_bytes()).unwrap();
+        return;
+    }
+    let result = if args.trace {
+        let policy = match args.algorithm {
+            Algorithms::Bfs => FrontierPolicy::Fifo,
+            Algorithms::ModBfs => FrontierPolicy::MinMaxCoinFlip,
+            Algorithms::Dfs => FrontierPolicy::Lifo,
+            _ => Cli::command()
+                .error(
+                    ErrorKind::ArgumentConflict,
+                    "--trace is only supported by bfs, dfs and mod-bfs",
+                )
+                .exit(),
+        };
+        graph.search_traced(args.starting_node, args.ending_node, policy, seed)
+    } else {
+        match args.algorithm {
+            Algorithms::Bfs => graph.bfs(args.starting_node, args.ending_node),
+            Algorithms::BidirectionalBfs => {
+                graph.bidirectional_bfs(args.starting_node, args.ending_node)
+            }
+            Algorithms::ModBfs => graph.modified_bfs(args.starting_node, args.ending_node, seed),
+            Algorithms::Dfs => graph.dfs(args.starting_node, args.ending_node),
+            Algorithms::Dls => graph.depth_limited_search(
+                args.starting_node,
+                args.ending_node,
+                args.limit.unwrap_or_default(),
+            ),
+            Algorithms::Iddfs => graph.iddfs(
+                args.starting_node,
+                args.ending_node,
+                args.limit.unwrap_or(graph.node_count()),
+            ),
+            Algorithms::Ucs => graph.ucs(args.starting_node, args.ending_node),
+            Algorithms::Astar => graph.astar(args.starting_node, args.ending_node, &heuristic),
+            Algorithms::IdaStar => graph.ida_star(args.starting_node, args.ending_node, &heuristic),
+            Algorithms::Greedy => {
+                graph.greedy_best_first(args.starting_node, args.ending_node, &heuristic)
+            }
+            Algorithms::Beam => graph.beam_search(
+                args.starting_node,
+                args.ending_node,
+                &heuristic,
+                args.width.unwrap_or_default(),
+            ),
+        }
     };
     let output = std::fs::File::create(args.output).unwrap();
     let mut writer = std::io::BufWriter::new(output);
     let mut table = Table::new();
-    table.set_header(vec![
+    let mut header = vec![
         "Node Count (n)",
         "Edge Count (m)",
         "Starting Node (v0)",
@@ -51,17 +266,83 @@ fn main() {
         "Distance",
         "Generated Nodes",
         "Expanded Nodes",
-    ]);
-    table.add_row(vec![
+    ];
+    let mut row = vec![
         graph.node_count().to_string(),
         graph.edge_count().to_string(),
         args.starting_node.to_string(),
         args.ending_node.to_string(),
-        result.str_path(args.starting_node, args.ending_node),
+        result.str_path(),
         result.distance.map_or_else(String::new, |f| f.to_string()),
         format!("{:?}", result.generated_nodes),
         format!("{:?}", result.expanded_nodes),
-    ]);
+    ];
+    if let Some(seed) = result.seed {
+        debug!(seed, "search used a random number generator");
+        header.push("Seed");
+        row.push(seed.to_string());
+    }
+    if !result.iterations.is_empty() {
+        header.push("Status");
+        row.push(result.status.to_string());
+        header.push("Iterations");
+        row.push(
+            result
+                .iterations
+                .iter()
+                .map(|iteration| {
+                    format!(
+                        "{}: {} generated, {} expanded, {}",
+                        iteration.bound, iteration.generated, iteration.expanded, iteration.status
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n"),
+        );
+    }
+    if let Some([forward, backward]) = &result.directions {
+        header.extend([
+            "Forward Generated",
+            "Forward Expanded",
+            "Backward Generated",
+            "Backward Expanded",
+        ]);
+        row.extend([
+            format!("{:?}", forward.generated),
+            format!("{:?}", forward.expanded),
+            format!("{:?}", backward.generated),
+            format!("{:?}", backward.expanded),
+        ]);
+    }
+    table.set_header(header);
+    table.add_row(row);
     writer.write_all(format!("{table}").as_bytes()).unwrap();
+    if !result.trace.is_empty() {
+        let mut trace = Table::new();
+        trace.set_header(vec![
+            "Iteration",
+            "Expanded Node",
+            "Frontier",
+            "Generated Nodes",
+            "Expanded Nodes",
+        ]);
+        for snapshot in &result.trace {
+            trace.add_row(vec![
+                snapshot.iteration.to_string(),
+                snapshot.expanded.to_string(),
+                format!(
+                    "{:?}",
+                    snapshot
+                        .frontier
+                        .iter()
+                        .map(|&(node, _)| node)
+                        .collect::<Vec<_>>()
+                ),
+                format!("{:?}", &result.generated_nodes[..snapshot.generated]),
+                format!("{:?}", &result.expanded_nodes[..snapshot.iteration]),
+            ]);
+        }
+        writer.write_all(format!("\n{trace}").as_bytes()).unwrap();
+    }
     debug!(result = ?result);
 }
@@ -1,9 +1,11 @@
+use std::{collections::HashMap, fmt};
+
 use nom::{
-    character::complete::{digit1, line_ending},
-    combinator::{map, map_res, opt, recognize},
-    multi::many_m_n,
+    character::complete::{digit1, line_ending, multispace0, space1},
+    combinator::{all_consuming, map, map_res, opt, recognize},
+    multi::{many0, many_m_n},
     number::complete::double,
-    sequence::delimited,
+    sequence::{delimited, separated_pair, terminated},
     IResult,
 };
 use tracing::{debug, trace};
@@ -41,3 +43,55 @@ pub fn parse_graph(input: &str) -> IResult<&str, GraphMap<usize, f64, Undirected
         },
     )(input)
 }
+
+/// A line of a heuristic table that is not a `node estimate` pair.
+#[derive(Debug)]
+pub struct MalformedLine {
+    /// The number of the line, starting at 1.
+    pub number: usize,
+    pub line: String,
+}
+
+impl fmt::Display for MalformedLine {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "line {} is not a `node estimate` pair: {:?}",
+            self.number, self.line
+        )
+    }
+}
+
+/// Parses a heuristic table, made of one `node estimate` pair per line.
+///
+/// Blank lines are skipped, but the whole input must be consumed, so a malformed line is
+/// reported as an error instead of silently ending the table.
+pub fn parse_heuristic(input: &str) -> Result<HashMap<usize, f64>, MalformedLine> {
+    let estimates = all_consuming(terminated(
+        many0(delimited(
+            multispace0,
+            separated_pair(usize, space1, double),
+            multispace0,
+        )),
+        multispace0,
+    ))(input);
+    match estimates {
+        Ok((_, estimates)) => {
+            debug!("Number of parsed estimates: {}", estimates.len());
+            Ok(estimates.into_iter().collect())
+        }
+        Err(nom::Err::Error(err) | nom::Err::Failure(err)) => {
+            let offset = input.len() - err.input.len();
+            let start = input[..offset].rfind('\n').map_or(0, |newline| newline + 1);
+            Err(MalformedLine {
+                number: input[..start].matches('\n').count() + 1,
+                line: input[start..]
+                    .lines()
+                    .next()
+                    .unwrap_or_default()
+                    .to_string(),
+            })
+        }
+        Err(nom::Err::Incomplete(_)) => unreachable!("complete parsers never need more input"),
+    }
+}
@@ -0,0 +1,40 @@
+//! Events emitted by the searches to an observer.
+//!
+//! Based of petgraph implementation.
+//!
+//! See https://github.com/petgraph/petgraph/blob/master/src/visit/dfsvisit.rs
+
+/// An event of a search, passed to the visitor of the search as it happens.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SearchEvent<TNode, TEdge> {
+    /// A node is reached for the first time with the given path cost, and is about to enter
+    /// the frontier. The start node is discovered before anything else. With
+    /// [`FrontierPolicy::LowestCost`](crate::FrontierPolicy::LowestCost) a node still in the
+    /// frontier is discovered again when a cheaper path to it is found.
+    Discover(TNode, TEdge),
+    /// A node is generated as a neighbor of the node being expanded, as `(parent, node)`.
+    /// This happens once per edge, whether or not the node was seen before.
+    Generate(TNode, TNode),
+    /// A node was taken from the frontier and is about to be expanded.
+    Expand(TNode),
+    /// All the neighbors of a node have been generated.
+    Finish(TNode),
+    /// A goal was taken from the frontier, even if the visitor pruned its expansion. With
+    /// [`GoalMode::First`](crate::GoalMode::First) the search stops after this event.
+    GoalReached(TNode),
+    /// A generated node is discarded because it was already seen.
+    Prune(TNode),
+}
+
+/// What the search does after the visitor handled an event.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Control {
+    /// Go on with the search.
+    Continue,
+    /// Skip the node of the event: a discovered or generated node does not enter the
+    /// frontier, and an expanded node does not generate its neighbors, although the goal
+    /// test is still applied to it. Ignored for the other events.
+    Prune,
+    /// Stop the search.
+    Break,
+}
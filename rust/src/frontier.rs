@@ -0,0 +1,154 @@
+//! Frontier selection policies used by the search algorithms.
+
+use std::collections::VecDeque;
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+/// Decides which node of the frontier is expanded next.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FrontierPolicy {
+    /// The oldest node of the frontier (breadth-first search).
+    Fifo,
+    /// The newest node of the frontier (depth-first search).
+    Lifo,
+    /// The node with the lowest id.
+    LowestId,
+    /// The node with the highest id.
+    HighestId,
+    /// A node chosen uniformly at random.
+    Random,
+    /// Either the node with the lowest or the one with the highest id, chosen at random.
+    ///
+    /// This is the frontier of the modified breadth-first search of the assignment.
+    MinMaxCoinFlip,
+    /// The node with the lowest cost from the start, ties broken by age.
+    ///
+    /// When a cheaper path to a node still in the frontier is found, the searches lower
+    /// its cost, so with non-negative weights this is a uniform-cost search.
+    LowestCost,
+}
+
+impl FrontierPolicy {
+    /// Returns `true` if the policy draws from the random number generator, i.e. if the
+    /// seed is needed to replay a search.
+    pub fn is_random(self) -> bool {
+        matches!(
+            self,
+            FrontierPolicy::Random | FrontierPolicy::MinMaxCoinFlip
+        )
+    }
+}
+
+/// The set of generated but not yet expanded nodes of a search, together with the cost
+/// of reaching each of them.
+#[derive(Clone, Debug)]
+pub struct Frontier<TNode, TEdge> {
+    policy: FrontierPolicy,
+    items: VecDeque<(TNode, TEdge)>,
+    rng: StdRng,
+}
+
+impl<TNode, TEdge> Frontier<TNode, TEdge>
+where
+    TNode: Ord,
+    TEdge: PartialOrd,
+{
+    /// Creates an empty frontier that pops nodes following the given policy.
+    ///
+    /// Random policies draw from a generator seeded with `seed`, so the same seed always
+    /// produces the same sequence of pops.
+    pub fn new(policy: FrontierPolicy, seed: u64) -> Self {
+        Self {
+            policy,
+            items: VecDeque::new(),
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    pub fn policy(&self) -> FrontierPolicy {
+        self.policy
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Adds a node reached with the given cost to the frontier.
+    pub fn push(&mut self, node: TNode, cost: TEdge) {
+        self.items.push_back((node, cost));
+    }
+
+    /// Lowers the cost of `node` to `cost` if it is in the frontier with a higher cost,
+    /// keeping its age. Returns `true` if the cost was lowered.
+    pub fn decrease_cost(&mut self, node: TNode, cost: TEdge) -> bool {
+        match self.items.iter_mut().find(|(other, _)| *other == node) {
+            Some((_, old)) if cost < *old => {
+                *old = cost;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Removes the next node to expand from the frontier, together with its cost.
+    pub fn pop(&mut self) -> Option<(TNode, TEdge)> {
+        if self.items.is_empty() {
+            return None;
+        }
+        let index = match self.policy {
+            FrontierPolicy::Fifo => 0,
+            FrontierPolicy::Lifo => self.items.len() - 1,
+            FrontierPolicy::LowestId => self.lowest_id(),
+            FrontierPolicy::HighestId => self.highest_id(),
+            FrontierPolicy::Random => self.rng.gen_range(0..self.items.len()),
+            FrontierPolicy::MinMaxCoinFlip => {
+                if self.rng.gen() {
+                    self.lowest_id()
+                } else {
+                    self.highest_id()
+                }
+            }
+            FrontierPolicy::LowestCost => self.lowest_cost(),
+        };
+        self.items.remove(index)
+    }
+
+    /// Returns an iterator over the nodes of the frontier, oldest first.
+    pub fn iter(&self) -> impl Iterator<Item = &(TNode, TEdge)> {
+        self.items.iter()
+    }
+
+    fn lowest_id(&self) -> usize {
+        let mut best = 0;
+        for (i, (node, _)) in self.items.iter().enumerate() {
+            if *node < self.items[best].0 {
+                best = i;
+            }
+        }
+        best
+    }
+
+    fn highest_id(&self) -> usize {
+        let mut best = 0;
+        for (i, (node, _)) in self.items.iter().enumerate() {
+            if *node > self.items[best].0 {
+                best = i;
+            }
+        }
+        best
+    }
+
+    fn lowest_cost(&self) -> usize {
+        let mut best = 0;
+        for (i, (_, cost)) in self.items.iter().enumerate() {
+            if *cost < self.items[best].1 {
+                best = i;
+            }
+        }
+        best
+    }
+}
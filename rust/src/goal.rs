@@ -0,0 +1,45 @@
+//! Goal tests used by the search algorithms.
+
+use std::{
+    collections::HashSet,
+    hash::{BuildHasher, Hash},
+};
+
+/// Decides whether a node is a goal of a search.
+///
+/// Closures `Fn(TNode) -> bool` are goal tests, and so are sets of nodes, where every node
+/// of the set is a goal.
+pub trait Goal<TNode> {
+    /// Returns `true` if `node` is a goal.
+    fn is_goal(&self, node: TNode) -> bool;
+}
+
+impl<TNode, F> Goal<TNode> for F
+where
+    F: Fn(TNode) -> bool,
+{
+    #[inline]
+    fn is_goal(&self, node: TNode) -> bool {
+        self(node)
+    }
+}
+
+impl<TNode, S> Goal<TNode> for HashSet<TNode, S>
+where
+    TNode: Eq + Hash,
+    S: BuildHasher,
+{
+    #[inline]
+    fn is_goal(&self, node: TNode) -> bool {
+        self.contains(&node)
+    }
+}
+
+/// Decides when a search with several goals stops.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GoalMode {
+    /// Stop as soon as any goal is reached.
+    First,
+    /// Keep searching until every reachable goal has been reached.
+    All,
+}
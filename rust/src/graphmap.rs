@@ -3,29 +3,44 @@
 //! See https://github.com/petgraph/petgraph/blob/master/src/graph_impl/mod.rs
 
 use std::{
-    collections::{HashMap, HashSet, VecDeque},
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap, HashSet},
     fmt,
     hash::Hash,
-    iter::Cloned,
+    iter::{Cloned, Map},
     marker::PhantomData,
     slice::Iter,
 };
 
-use indexmap::{map::Keys, IndexMap};
-use tracing::info;
+use indexmap::{map::Keys, IndexMap, IndexSet};
 
-use crate::{iterator_wrap, Direction, EdgeType, Incoming, Outgoing, Undirected};
+use crate::{
+    bfs_with, dfs_with, graph_search, ida_star, iterator_wrap, multi_source_uniform_cost_search,
+    scored::MinScored, uniform_cost_search, Control, Directed, Direction, EdgeType, Frontier,
+    FrontierPolicy, FrontierSearch, Goal, GoalMode, GraphProblem, Heuristic, Incoming, Outgoing,
+    SearchEvent, Undirected,
+};
 
 /// A graph data structure.
 /// This is a general purpose graph data structure that can be used to represent
 /// both directed and undirected graphs.
-#[derive(Clone)]
 pub struct GraphMap<TNode, TEdge, Ty = Undirected> {
     nodes: IndexMap<TNode, Vec<(TNode, CompactDirection)>>,
     edges: IndexMap<(TNode, TNode), TEdge>,
     ty: PhantomData<Ty>,
 }
 
+impl<TNode: Clone, TEdge: Clone, Ty> Clone for GraphMap<TNode, TEdge, Ty> {
+    // implemented by hand so that `Ty` does not need to be `Clone`
+    fn clone(&self) -> Self {
+        GraphMap {
+            nodes: self.nodes.clone(),
+            edges: self.edges.clone(),
+            ty: PhantomData,
+        }
+    }
+}
+
 impl<N: Eq + Hash + fmt::Debug, E: fmt::Debug, Ty: EdgeType> fmt::Debug for GraphMap<N, E, Ty> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         self.nodes.fmt(f)
@@ -38,7 +53,7 @@ impl<TNode> NodeTrait for TNode where TNode: Eq + Hash + Copy + Ord + PartialEq
 impl<TNode, TEdge, Ty> GraphMap<TNode, TEdge, Ty>
 where
     TNode: NodeTrait + std::fmt::Debug,
-    TEdge: Default + Copy + std::ops::Add<Output = TEdge> + std::fmt::Debug,
+    TEdge: Default + Copy + PartialOrd + std::ops::Add<Output = TEdge> + std::fmt::Debug,
     Ty: EdgeType,
 {
     /// Creates a new [`GraphMap<TNode, TEdge, Ty>`].
@@ -75,11 +90,13 @@ where
 
     /// Adds a node to the graph.
     pub fn add_node(&mut self, node: TNode) -> TNode {
-        self.nodes.entry(node).or_insert(Vec::new());
+        self.nodes.entry(node).or_default();
         node
     }
 
-    /// Removes a node from the graph.
+    /// Removes a node from the graph, together with every edge incident to it.
+    ///
+    /// Returns `true` if the node was present in the graph.
     pub fn remove_node(&mut self, node: TNode) -> bool {
         let links = match self.nodes.swap_remove(&node) {
             Some(links) => links,
@@ -87,12 +104,13 @@ where
         };
         for (succ, dir) in links {
             let edge = if dir == CompactDirection::Outgoing {
-                todo!("remove edge");
-                // (node, succ)
+                (node, succ)
             } else {
-                todo!("remove edge");
-                // (succ, node)
+                (succ, node)
             };
+            // remove the reverse link from the other endpoint
+            self.remove_single_edge(&succ, &node, dir.opposite());
+            self.edges.swap_remove(&Self::edge_key(edge.0, edge.1));
         }
         true
     }
@@ -101,8 +119,12 @@ where
         self.nodes.contains_key(&node)
     }
 
+    /// Adds an edge from `from` to `to` with the given weight.
+    ///
+    /// If the edge already exists its weight is replaced and the old weight is returned.
+    /// For undirected graphs `add_edge(a, b, _)` and `add_edge(b, a, _)` refer to the same edge.
     pub fn add_edge(&mut self, from: TNode, to: TNode, weight: TEdge) -> Option<TEdge> {
-        if let old @ Some(_) = self.edges.insert((from, to), weight) {
+        if let old @ Some(_) = self.edges.insert(Self::edge_key(from, to), weight) {
             old
         } else {
             self.nodes
@@ -119,28 +141,58 @@ where
         }
     }
 
+    /// Removes the adjacency entry `to` from the adjacency list of `from`.
+    ///
+    /// For directed graphs only the entry with the given direction is removed, for
+    /// undirected graphs the direction is ignored. The `edges` map is left untouched.
+    ///
+    /// Returns `true` if an entry was removed.
     pub fn remove_single_edge(&mut self, from: &TNode, to: &TNode, dir: CompactDirection) -> bool {
-        todo!();
+        let links = match self.nodes.get_mut(from) {
+            Some(links) => links,
+            None => return false,
+        };
+        let position = if Ty::is_directed() {
+            links.iter().position(|link| link == &(*to, dir))
+        } else {
+            links.iter().position(|(node, _)| node == to)
+        };
+        match position {
+            Some(index) => {
+                links.swap_remove(index);
+                true
+            }
+            None => false,
+        }
     }
 
+    /// Removes the edge between `from` and `to` from the graph.
+    ///
+    /// Returns the weight of the removed edge, or `None` if there was no such edge.
     pub fn remove_edge(&mut self, from: &TNode, to: &TNode) -> Option<TEdge> {
-        todo!();
+        let weight = self.edges.swap_remove(&Self::edge_key(*from, *to))?;
+        self.remove_single_edge(from, to, CompactDirection::Outgoing);
+        if from != to {
+            self.remove_single_edge(to, from, CompactDirection::Incoming);
+        }
+        Some(weight)
     }
 
+    /// Returns `true` if there is an edge from `from` to `to`.
+    ///
+    /// For undirected graphs the order of the endpoints does not matter.
     pub fn contains_edge(&self, from: TNode, to: TNode) -> bool {
-        self.edges.contains_key(&(from, to))
+        self.edges.contains_key(&Self::edge_key(from, to))
     }
 
+    /// Returns the weight of the edge from `from` to `to`, if any.
+    ///
+    /// For undirected graphs the order of the endpoints does not matter.
     pub fn get_edge(&self, from: TNode, to: TNode) -> Option<&TEdge> {
-        // TODO: Check for directionality
-        if let value @ Some(_) = self.edges.get(&(from, to)) {
-            value
-        } else {
-            self.edges.get(&(to, from))
-        }
+        self.edges.get(&Self::edge_key(from, to))
     }
 
-    pub fn nodes(&self) -> Nodes<TNode> {
+    pub fn nodes(&self) -> Nodes<'_, TNode> {
         Nodes {
             iter: self.nodes.keys().cloned(),
         }
@@ -155,9 +207,69 @@ where
     /// # Returns
     ///
     /// A `Neighbors` struct containing the neighbors of the given node and their edge weights.
-    pub fn neighbors(&self, node: TNode) -> Neighbors<TNode, Ty> {
+    /// A node that is not in the graph has no neighbors.
+    pub fn neighbors(&self, node: TNode) -> Neighbors<'_, TNode, Ty> {
         Neighbors {
-            iter: self.nodes[&node].iter(),
+            iter: self.links(node).iter(),
+            ty: PhantomData,
+        }
+    }
+
+    /// Returns the neighbors of a given node in the given direction.
+    ///
+    /// For directed graphs [`Outgoing`] yields the successors of `node` and [`Incoming`]
+    /// its predecessors. For undirected graphs the direction is ignored and every
+    /// neighbor is produced. A node that is not in the graph has no neighbors.
+    pub fn neighbors_directed(
+        &self,
+        node: TNode,
+        dir: Direction,
+    ) -> NeighborsDirected<'_, TNode, Ty> {
+        NeighborsDirected {
+            from: node,
+            iter: self.links(node).iter(),
+            dir,
+            ty: PhantomData,
+        }
+    }
+
+    /// Returns an iterator over all the edges of the graph.
+    ///
+    /// Each item is a tuple `(a, b, &weight)` and every edge is produced exactly once.
+    pub fn all_edges(&self) -> AllEdges<'_, TNode, TEdge> {
+        AllEdges {
+            iter: self.edges.iter().map(|(&(a, b), weight)| (a, b, weight)),
+        }
+    }
+
+    /// Returns an iterator over the edges reachable from a given node.
+    ///
+    /// Each item is a tuple `(node, neighbor, &weight)`. For directed graphs only the
+    /// outgoing edges are produced. A node that is not in the graph has no edges.
+    pub fn edges(&self, node: TNode) -> Edges<'_, TNode, TEdge, Ty> {
+        Edges {
+            from: node,
+            edges: &self.edges,
+            iter: self.links(node).iter(),
+            ty: PhantomData,
+        }
+    }
+
+    /// Returns an iterator over the edges of a given node in the given direction.
+    ///
+    /// For directed graphs each item is a tuple `(source, target, &weight)`, so
+    /// [`Outgoing`] edges start at `node` and [`Incoming`] edges end at it. For undirected
+    /// graphs the direction is ignored and every edge is produced as `(node, neighbor, &weight)`.
+    pub fn edges_directed(
+        &self,
+        node: TNode,
+        dir: Direction,
+    ) -> EdgesDirected<'_, TNode, TEdge, Ty> {
+        EdgesDirected {
+            from: node,
+            dir,
+            edges: &self.edges,
+            iter: self.links(node).iter(),
             ty: PhantomData,
         }
     }
@@ -176,16 +288,29 @@ where
         acc
     }
 
-    fn min_max_node(mut nodes: Vec<TNode>) -> (TNode, TNode) {
-        nodes.sort();
-        let min = nodes[0];
-        let max = nodes[nodes.len() - 1];
-        (min, max)
+    /// Returns the total weight of the edges of a path, given as the nodes it visits in order,
+    /// or `None` if two consecutive nodes of the path are not joined by an edge.
+    pub fn path_weight(&self, path: &[TNode]) -> Option<TEdge> {
+        path.windows(2).try_fold(TEdge::default(), |acc, pair| {
+            Some(acc + *self.get_edge(pair[0], pair[1])?)
+        })
+    }
+
+    /// Returns the problem of reaching `goal` from `start` in the graph, which can be solved
+    /// by the searches over a [`SearchProblem`](crate::SearchProblem).
+    pub fn problem(
+        &self,
+        start: TNode,
+        goal: TNode,
+    ) -> GraphProblem<&Self, impl Fn(TNode) -> bool + Copy> {
+        GraphProblem::new(self, start, move |node| node == goal)
     }
 
     /// Performs a breadth-first search on the graph, starting from the given start node and
     /// searching for the given goal node.
     ///
+    /// The frontier is a FIFO queue, so the path found has the fewest edges possible.
+    ///
     /// # Arguments
     ///
     /// * `start` - The starting node for the search.
@@ -197,74 +322,212 @@ where
     /// from the start node to the goal node (if one was found), the number of nodes visited, and
     /// the number of edges traversed.
     pub fn bfs(&self, start: TNode, goal: TNode) -> GraphSearchReport<TNode, TEdge> {
+        self.search(start, goal, FrontierPolicy::Fifo, 0)
+    }
+
+    /// Performs the modified breadth-first search required by the assignment.
+    ///
+    /// Instead of taking the oldest node of the frontier, the node to expand is chosen at
+    /// random between the frontier nodes with the lowest and the highest id. The random
+    /// choices are drawn from a generator seeded with `seed`, which is recorded in the report.
+    pub fn modified_bfs(
+        &self,
+        start: TNode,
+        goal: TNode,
+        seed: u64,
+    ) -> GraphSearchReport<TNode, TEdge> {
+        self.search(start, goal, FrontierPolicy::MinMaxCoinFlip, seed)
+    }
+
+    /// Performs a depth-first search on the graph, starting from the given start node and
+    /// searching for the given goal node.
+    pub fn dfs(&self, start: TNode, goal: TNode) -> GraphSearchReport<TNode, TEdge> {
+        self.search(start, goal, FrontierPolicy::Lifo, 0)
+    }
+
+    /// Performs a breadth-first search on the graph, like [`GraphMap::bfs`], calling
+    /// `visitor` on every event of the search.
+    ///
+    /// See [`frontier_search_with`](crate::frontier_search_with) for how the visitor
+    /// controls the search.
+    pub fn bfs_with<V>(
+        &self,
+        start: TNode,
+        goal: TNode,
+        visitor: V,
+    ) -> GraphSearchReport<TNode, TEdge>
+    where
+        V: FnMut(SearchEvent<TNode, TEdge>) -> Control,
+    {
+        bfs_with(self, start, goal, visitor)
+    }
+
+    /// Performs a depth-first search on the graph, like [`GraphMap::dfs`], calling
+    /// `visitor` on every event of the search.
+    ///
+    /// See [`frontier_search_with`](crate::frontier_search_with) for how the visitor
+    /// controls the search.
+    pub fn dfs_with<V>(
+        &self,
+        start: TNode,
+        goal: TNode,
+        visitor: V,
+    ) -> GraphSearchReport<TNode, TEdge>
+    where
+        V: FnMut(SearchEvent<TNode, TEdge>) -> Control,
+    {
+        dfs_with(self, start, goal, visitor)
+    }
+
+    /// Performs a breadth-first search on the graph, starting from the given start node and
+    /// searching for the nodes that satisfy `goal`.
+    ///
+    /// See [`GraphMap::search_where`] for the details.
+    pub fn bfs_where<G>(
+        &self,
+        start: TNode,
+        goal: &G,
+        mode: GoalMode,
+    ) -> GraphSearchReport<TNode, TEdge>
+    where
+        G: Goal<TNode> + ?Sized,
+    {
+        self.search_where(start, goal, mode, FrontierPolicy::Fifo, 0)
+    }
+
+    /// Performs a depth-first search on the graph, starting from the given start node and
+    /// searching for the nodes that satisfy `goal`.
+    ///
+    /// See [`GraphMap::search_where`] for the details.
+    pub fn dfs_where<G>(
+        &self,
+        start: TNode,
+        goal: &G,
+        mode: GoalMode,
+    ) -> GraphSearchReport<TNode, TEdge>
+    where
+        G: Goal<TNode> + ?Sized,
+    {
+        self.search_where(start, goal, mode, FrontierPolicy::Lifo, 0)
+    }
+
+    /// Performs a uniform-cost search (Dijkstra's algorithm) on the graph, starting from the
+    /// given start node and searching for the given goal node.
+    ///
+    /// Nodes are expanded in order of increasing path cost, so the path found is the one
+    /// with the lowest distance as long as no edge has a negative weight. A node is
+    /// generated every time it is reached through an edge, and expanded the first time it
+    /// is popped from the priority queue.
+    ///
+    /// This is [`uniform_cost_search`] on the problem of [`GraphMap::problem`].
+    pub fn ucs(&self, start: TNode, goal: TNode) -> GraphSearchReport<TNode, TEdge> {
+        uniform_cost_search(&self.problem(start, goal))
+    }
+
+    /// Performs an A* search on the graph, starting from the given start node and searching
+    /// for the given goal node.
+    ///
+    /// Nodes are expanded in order of increasing `g + h`, where `g` is the cost of the best
+    /// known path to the node and `h` the estimate given by the heuristic. The path found is
+    /// the one with the lowest distance if the heuristic never overestimates the real cost.
+    /// Expanded nodes are reopened when a cheaper path to them is found, so inconsistent
+    /// heuristics are supported too.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - The starting node for the search.
+    /// * `goal` - The goal node to search for.
+    /// * `heuristic` - The estimate of the cost of reaching `goal` from each node.
+    pub fn astar<H>(
+        &self,
+        start: TNode,
+        goal: TNode,
+        heuristic: &H,
+    ) -> GraphSearchReport<TNode, TEdge>
+    where
+        H: Heuristic<TNode, TEdge> + ?Sized,
+    {
         let mut parents = HashMap::new();
-        let mut queue = vec![start];
-        let mut seen = HashSet::new();
+        let mut costs = HashMap::new();
+        let mut closed = HashSet::new();
+        let mut queue = BinaryHeap::new();
         let mut generated = Vec::new();
         let mut expanded = Vec::new();
-        let mut acc = TEdge::default();
-        seen.insert(start);
+        costs.insert(start, TEdge::default());
+        queue.push(MinScored(
+            heuristic.estimate(start),
+            (TEdge::default(), start),
+        ));
         generated.push(start);
-        while !queue.is_empty() {
-            let node = {
-                let mut min = None;
-                let mut max = None;
-                for (i, node) in queue.iter().enumerate() {
-                    if min.is_none() || node < queue.get(min.unwrap()).unwrap() {
-                        min = Some(i);
-                    }
-                    if max.is_none() || node > queue.get(max.unwrap()).unwrap() {
-                        max = Some(i);
-                    }
-                }
-                // choose min or max randomly
-                let chosen_index = if rand::random() {
-                    min.unwrap()
-                } else {
-                    max.unwrap()
-                };
-                let copy = *queue.get(chosen_index).unwrap();
-                queue.swap_remove(chosen_index);
-                copy
-            };
+        while let Some(MinScored(_, (cost, node))) = queue.pop() {
+            if !closed.insert(node) {
+                // a cheaper path to this node was already expanded
+                continue;
+            }
             expanded.push(node);
             if node == goal {
                 break;
             }
-            for neighbor in self.neighbors(node) {
+            for (_, neighbor, &weight) in self.edges(node) {
                 generated.push(neighbor);
-                if seen.insert(neighbor) {
-                    acc = acc + *self.get_edge(node, neighbor).unwrap();
+                let next_cost = cost + weight;
+                if costs.get(&neighbor).is_none_or(|&old| next_cost < old) {
+                    costs.insert(neighbor, next_cost);
                     parents.insert(neighbor, node);
-                    queue.push(neighbor);
+                    closed.remove(&neighbor);
+                    let estimate = next_cost + heuristic.estimate(neighbor);
+                    queue.push(MinScored(estimate, (next_cost, neighbor)));
                 }
             }
         }
-        if parents.get(&goal).is_none() {
-            return GraphSearchReport {
-                path: None,
-                distance: None,
-                generated_nodes: generated,
-                expanded_nodes: expanded,
-            };
-        }
-        GraphSearchReport {
-            path: Some(parents.clone()),
-            distance: Some(self.path_cost(parents, goal)),
-            generated_nodes: generated,
-            expanded_nodes: expanded,
-        }
+        self.report(parents, start, goal, generated, expanded)
     }
 
-    pub fn dfs(&self, start: TNode, goal: TNode) -> GraphSearchReport<TNode, TEdge> {
+    /// Performs an iterative deepening A* search (IDA*) on the graph, starting from the given
+    /// start node and searching for the given goal node.
+    ///
+    /// Unlike [`GraphMap::astar`] no closed set is kept, see [`ida_star`] for the details.
+    pub fn ida_star<H>(
+        &self,
+        start: TNode,
+        goal: TNode,
+        heuristic: &H,
+    ) -> GraphSearchReport<TNode, TEdge>
+    where
+        H: Heuristic<TNode, TEdge> + ?Sized,
+    {
+        ida_star(
+            start,
+            |node| self.edges(node).map(|(_, next, &weight)| (next, weight)),
+            |node| node == goal,
+            heuristic,
+        )
+    }
+
+    /// Performs a greedy best-first search on the graph, starting from the given start node
+    /// and searching for the given goal node.
+    ///
+    /// The node expanded next is always the one the heuristic estimates to be closest to the
+    /// goal, ignoring the cost of reaching it, so the path found may not be the shortest.
+    /// Nodes are marked as seen when generated, as in [`GraphMap::search`].
+    pub fn greedy_best_first<H>(
+        &self,
+        start: TNode,
+        goal: TNode,
+        heuristic: &H,
+    ) -> GraphSearchReport<TNode, TEdge>
+    where
+        H: Heuristic<TNode, TEdge> + ?Sized,
+    {
         let mut parents = HashMap::new();
-        let mut stack = vec![start];
+        let mut frontier = Frontier::new(FrontierPolicy::LowestCost, 0);
         let mut seen = HashSet::new();
         let mut generated = Vec::new();
         let mut expanded = Vec::new();
+        frontier.push(start, heuristic.estimate(start));
         seen.insert(start);
         generated.push(start);
-        while let Some(node) = stack.pop() {
+        while let Some((node, _)) = frontier.pop() {
             expanded.push(node);
             if node == goal {
                 break;
@@ -273,130 +536,1200 @@ where
                 generated.push(neighbor);
                 if seen.insert(neighbor) {
                     parents.insert(neighbor, node);
-                    stack.push(neighbor);
+                    frontier.push(neighbor, heuristic.estimate(neighbor));
                 }
             }
         }
-        if parents.get(&goal).is_none() {
-            return GraphSearchReport {
-                path: None,
-                distance: None,
-                generated_nodes: generated,
-                expanded_nodes: expanded,
-            };
+        self.report(parents, start, goal, generated, expanded)
+    }
+
+    /// Performs a beam search on the graph, starting from the given start node and searching
+    /// for the given goal node.
+    ///
+    /// This is a breadth-first search that only keeps the `width` nodes of each level that
+    /// the heuristic estimates to be closest to the goal, so it may miss the goal even if it
+    /// is reachable. Ties are broken in favour of the nodes generated first. If the goal is
+    /// not found after some candidates were left out of the beam, the status of the report
+    /// is [`SearchStatus::Cutoff`] rather than [`SearchStatus::Failure`].
+    pub fn beam_search<H>(
+        &self,
+        start: TNode,
+        goal: TNode,
+        heuristic: &H,
+        width: usize,
+    ) -> GraphSearchReport<TNode, TEdge>
+    where
+        H: Heuristic<TNode, TEdge> + ?Sized,
+    {
+        let mut parents = HashMap::new();
+        let mut beam = vec![start];
+        let mut seen = HashSet::new();
+        let mut generated = Vec::new();
+        let mut expanded = Vec::new();
+        let mut dropped = false;
+        seen.insert(start);
+        generated.push(start);
+        'search: while !beam.is_empty() {
+            let mut candidates = Vec::new();
+            for node in beam {
+                expanded.push(node);
+                if node == goal {
+                    break 'search;
+                }
+                for neighbor in self.neighbors(node) {
+                    generated.push(neighbor);
+                    if seen.insert(neighbor) {
+                        candidates.push((heuristic.estimate(neighbor), neighbor, node));
+                    }
+                }
+            }
+            candidates.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal));
+            dropped |= candidates.len() > width;
+            // only the nodes that stay in the beam become part of the search tree
+            beam = candidates
+                .into_iter()
+                .take(width)
+                .map(|(_, neighbor, node)| {
+                    parents.insert(neighbor, node);
+                    neighbor
+                })
+                .collect();
         }
-        GraphSearchReport {
-            path: Some(parents.clone()),
-            distance: Some(self.path_cost(parents, goal)),
-            generated_nodes: generated,
-            expanded_nodes: expanded,
+        let mut report = self.report(parents, start, goal, generated, expanded);
+        if dropped && report.status == SearchStatus::Failure {
+            report.status = SearchStatus::Cutoff;
         }
+        report
     }
-}
 
-impl<TNode, TEdge, Ty> Default for GraphMap<TNode, TEdge, Ty>
-where
-    TNode: NodeTrait,
-{
-    /// Creates an empty [`GraphMap<TNode, TEdge, Ty>`].
-    fn default() -> Self {
-        Self {
-            nodes: IndexMap::new(),
-            edges: IndexMap::new(),
-            ty: PhantomData,
+    /// Returns an iterator over every simple path from `start` to `goal`, produced lazily as
+    /// the nodes it visits in order together with its distance.
+    ///
+    /// Only the paths with at least `min_len` and at most `max_len` edges are produced, with
+    /// no upper bound if `max_len` is `None`. The paths are found with a depth-first search,
+    /// which accumulates the distance of the current path as it descends.
+    pub fn all_simple_paths(
+        &self,
+        start: TNode,
+        goal: TNode,
+        min_len: usize,
+        max_len: Option<usize>,
+    ) -> AllSimplePaths<'_, TNode, TEdge, Ty> {
+        let max_len = max_len.unwrap_or(usize::MAX);
+        let trivial = start == goal && min_len == 0;
+        AllSimplePaths {
+            graph: self,
+            goal,
+            min_len,
+            max_len,
+            visited: IndexSet::from([start]),
+            costs: vec![TEdge::default()],
+            stack: if start == goal {
+                Vec::new()
+            } else {
+                vec![self.edges(start)]
+            },
+            trivial: trivial.then(|| (vec![start], TEdge::default())),
         }
     }
-}
-#[derive(Clone, Debug)]
-pub struct GraphSearchReport<TNode, TEdge> {
-    pub path: Option<HashMap<TNode, TNode>>,
-    pub distance: Option<TEdge>,
-    pub generated_nodes: Vec<TNode>,
-    pub expanded_nodes: Vec<TNode>,
-}
 
-impl<TNode, TEdge> GraphSearchReport<TNode, TEdge> {
-    pub fn str_path(&self, start: TNode, goal: TNode) -> String
-    where
-        TNode: NodeTrait + std::fmt::Display,
-    {
-        let mut acc = String::new();
-        let path = match &self.path {
-            Some(path) => path,
-            None => return acc,
-        };
-        let mut next: Option<TNode> = Some(goal);
-        while let Some(node) = next {
-            if let Some(parent) = path.get(&node) {
-                match acc.len() {
-                    0 => acc = format!("{}", node),
-                    _ => acc = format!("{} -> {}", node, acc),
+    /// Finds the `k` shortest loopless paths from `start` to `goal`, using Yen's algorithm.
+    ///
+    /// The paths are returned ranked by increasing distance, each with its distance, and may
+    /// be less than `k` if there are not enough loopless paths. Edge weights must not be
+    /// negative.
+    pub fn k_shortest_paths(
+        &self,
+        start: TNode,
+        goal: TNode,
+        k: usize,
+    ) -> Vec<(Vec<TNode>, TEdge)> {
+        let mut paths: Vec<(Vec<TNode>, TEdge)> = Vec::new();
+        let mut candidates: Vec<(Vec<TNode>, TEdge)> = Vec::new();
+        match self.shortest_path(start, goal) {
+            Some(path) if k > 0 => paths.push(path),
+            _ => return paths,
+        }
+        while paths.len() < k {
+            let last = paths[paths.len() - 1].0.clone();
+            for i in 0..last.len() - 1 {
+                let (spur, root) = (last[i], &last[..=i]);
+                // deviate from every path found so far that shares this root
+                let mut graph = self.clone();
+                for (path, _) in &paths {
+                    if path.len() > i + 1 && &path[..=i] == root {
+                        graph.remove_edge(&path[i], &path[i + 1]);
+                    }
                 }
-                next = Some(*parent);
-            } else {
+                for &node in &root[..i] {
+                    graph.remove_node(node);
+                }
+                let Some((spur_path, _)) = graph.shortest_path(spur, goal) else {
+                    continue;
+                };
+                let mut path = root[..i].to_vec();
+                path.extend(spur_path);
+                if candidates
+                    .iter()
+                    .chain(&paths)
+                    .any(|(other, _)| *other == path)
+                {
+                    continue;
+                }
+                let distance = self.path_weight(&path).unwrap();
+                candidates.push((path, distance));
+            }
+            if candidates.is_empty() {
                 break;
             }
+            let mut best = 0;
+            for (i, (_, distance)) in candidates.iter().enumerate() {
+                if *distance < candidates[best].1 {
+                    best = i;
+                }
+            }
+            paths.push(candidates.remove(best));
         }
-        format!("{} -> {}", start, acc)
+        paths
     }
-}
 
-#[derive(Clone, Copy, Debug, PartialEq)]
-pub enum CompactDirection {
-    Outgoing,
-    Incoming,
-}
+    /// Returns the lowest cost path from `start` to `goal` and its distance.
+    fn shortest_path(&self, start: TNode, goal: TNode) -> Option<(Vec<TNode>, TEdge)> {
+        let report = self.ucs(start, goal);
+        Some((report.path?, report.distance?))
+    }
 
-impl CompactDirection {
-    /// Return the opposite `CompactDirection`.
-    #[inline]
-    pub fn opposite(self) -> CompactDirection {
-        match self {
-            CompactDirection::Outgoing => CompactDirection::Incoming,
-            CompactDirection::Incoming => CompactDirection::Outgoing,
-        }
+    /// Computes the lowest path cost from `start` to every node reachable from it, using
+    /// Dijkstra's algorithm.
+    ///
+    /// Edge weights must not be negative.
+    pub fn dijkstra(&self, start: TNode) -> HashMap<TNode, TEdge> {
+        self.reweighted_dijkstra(start, |_, _, weight| weight).0
     }
-}
 
-impl From<CompactDirection> for Direction {
-    fn from(dir: CompactDirection) -> Self {
-        match dir {
-            CompactDirection::Outgoing => Outgoing,
-            CompactDirection::Incoming => Incoming,
+    /// Runs Dijkstra's algorithm from `start` on the graph with every edge weighted by
+    /// `reweight`, which is given the source, target and weight of the edge.
+    ///
+    /// Returns the lowest cost to every node reachable from `start`, and the first node
+    /// after `start` in the lowest cost path to each of them, which is `start` itself for
+    /// `start`. The new weights must not be negative.
+    fn reweighted_dijkstra<W>(
+        &self,
+        start: TNode,
+        reweight: W,
+    ) -> (HashMap<TNode, TEdge>, HashMap<TNode, TNode>)
+    where
+        W: Fn(TNode, TNode, TEdge) -> TEdge,
+    {
+        let mut costs = HashMap::from([(start, TEdge::default())]);
+        let mut hops = HashMap::from([(start, start)]);
+        let mut closed = HashSet::new();
+        let mut queue = BinaryHeap::new();
+        queue.push(MinScored(TEdge::default(), start));
+        while let Some(MinScored(cost, node)) = queue.pop() {
+            if !closed.insert(node) {
+                continue;
+            }
+            for (_, neighbor, &weight) in self.edges(node) {
+                if closed.contains(&neighbor) {
+                    continue;
+                }
+                let next_cost = cost + reweight(node, neighbor, weight);
+                if costs.get(&neighbor).is_none_or(|&old| next_cost < old) {
+                    costs.insert(neighbor, next_cost);
+                    hops.insert(neighbor, if node == start { neighbor } else { hops[&node] });
+                    queue.push(MinScored(next_cost, neighbor));
+                }
+            }
         }
+        (costs, hops)
     }
-}
 
-impl From<Direction> for CompactDirection {
-    fn from(dir: Direction) -> Self {
-        match dir {
-            Outgoing => CompactDirection::Outgoing,
-            Incoming => CompactDirection::Incoming,
+    /// Performs a depth-limited search on the graph, starting from the given start node and
+    /// searching for the given goal node.
+    ///
+    /// This is a depth-first tree search that does not expand nodes deeper than `limit`
+    /// edges from the start, and that only avoids the nodes of the current path, so it
+    /// needs memory proportional to the limit. The status of the report is
+    /// [`SearchStatus::Cutoff`] if the limit prevented exploring part of the graph, and
+    /// [`SearchStatus::Failure`] if the whole reachable graph was explored without finding
+    /// the goal.
+    pub fn depth_limited_search(
+        &self,
+        start: TNode,
+        goal: TNode,
+        limit: usize,
+    ) -> GraphSearchReport<TNode, TEdge> {
+        let mut generated = vec![start];
+        let mut expanded = Vec::new();
+        let (status, parents) =
+            self.depth_limited(start, goal, limit, &mut generated, &mut expanded);
+        let iteration = SearchIteration {
+            bound: SearchBound::Depth(limit),
+            generated: generated.len(),
+            expanded: expanded.len(),
+            status,
+        };
+        GraphSearchReport {
+            status,
+            iterations: vec![iteration],
+            ..self.report(parents, start, goal, generated, expanded)
         }
     }
-}
 
-impl PartialEq<Direction> for CompactDirection {
-    fn eq(&self, rhs: &Direction) -> bool {
-        (*self as usize) == (*rhs as usize)
+    /// Performs an iterative deepening depth-first search on the graph, starting from the
+    /// given start node and searching for the given goal node.
+    ///
+    /// Runs [`GraphMap::depth_limited_search`] with limits `0, 1, ..., max_depth` until the
+    /// goal is found or a search fails without being cut off. The generated and expanded
+    /// nodes of every iteration are accumulated in the report, and the counts of each
+    /// iteration are recorded in [`GraphSearchReport::iterations`].
+    pub fn iddfs(
+        &self,
+        start: TNode,
+        goal: TNode,
+        max_depth: usize,
+    ) -> GraphSearchReport<TNode, TEdge> {
+        let mut generated = Vec::new();
+        let mut expanded = Vec::new();
+        let mut iterations = Vec::new();
+        let mut status = SearchStatus::Failure;
+        let mut parents = HashMap::new();
+        for limit in 0..=max_depth {
+            let mut iteration_generated = vec![start];
+            let mut iteration_expanded = Vec::new();
+            (status, parents) = self.depth_limited(
+                start,
+                goal,
+                limit,
+                &mut iteration_generated,
+                &mut iteration_expanded,
+            );
+            iterations.push(SearchIteration {
+                bound: SearchBound::Depth(limit),
+                generated: iteration_generated.len(),
+                expanded: iteration_expanded.len(),
+                status,
+            });
+            generated.append(&mut iteration_generated);
+            expanded.append(&mut iteration_expanded);
+            if status != SearchStatus::Cutoff {
+                break;
+            }
+        }
+        GraphSearchReport {
+            status,
+            iterations,
+            ..self.report(parents, start, goal, generated, expanded)
+        }
     }
-}
 
-iterator_wrap!(
-    impl (Iterator DoubleEndedIterator ExactSizeIterator) for
-    #[derive(Clone, Debug)]
-    struct Nodes<'a, TNode> where { TNode: 'a + NodeTrait }
-    item: TNode,
-    iter: Cloned<Keys<'a, TNode, Vec<(TNode, CompactDirection)>>>,
-);
+    /// Runs a single depth-limited search, returning its status and the path found as a
+    /// search tree.
+    fn depth_limited(
+        &self,
+        start: TNode,
+        goal: TNode,
+        limit: usize,
+        generated: &mut Vec<TNode>,
+        expanded: &mut Vec<TNode>,
+    ) -> (SearchStatus, HashMap<TNode, TNode>) {
+        let mut path = vec![start];
+        let mut stack = vec![self.neighbors(start)];
+        let mut cutoff = false;
+        expanded.push(start);
+        if start == goal {
+            return (SearchStatus::Found, HashMap::new());
+        }
+        if limit == 0 {
+            return (SearchStatus::Cutoff, HashMap::new());
+        }
+        while let Some(successors) = stack.last_mut() {
+            let Some(node) = successors.next() else {
+                stack.pop();
+                path.pop();
+                continue;
+            };
+            generated.push(node);
+            if path.contains(&node) {
+                continue;
+            }
+            expanded.push(node);
+            if node == goal {
+                path.push(node);
+                let parents = path.windows(2).map(|pair| (pair[1], pair[0])).collect();
+                return (SearchStatus::Found, parents);
+            }
+            if path.len() == limit {
+                cutoff = true;
+                continue;
+            }
+            path.push(node);
+            stack.push(self.neighbors(node));
+        }
+        let status = if cutoff {
+            SearchStatus::Cutoff
+        } else {
+            SearchStatus::Failure
+        };
+        (status, HashMap::new())
+    }
 
-#[derive(Clone, Debug)]
-pub struct Neighbors<'a, TNode, Ty = Undirected>
-where
-    TNode: 'a,
-    Ty: EdgeType,
-{
-    iter: Iter<'a, (TNode, CompactDirection)>,
+    /// Performs a bidirectional breadth-first search on the graph, searching forward from the
+    /// start node and backward from the goal node until both searches meet.
+    ///
+    /// The backward search follows the edges in reverse, using the predecessors of each
+    /// node on directed graphs. The searches alternate one whole level at a time, always
+    /// advancing the one with the smallest frontier, and once they meet the level is
+    /// finished so that the path found has the fewest edges possible.
+    ///
+    /// The generated and expanded nodes of each search are recorded in
+    /// [`GraphSearchReport::directions`], indexed by [`Direction::index`] with [`Outgoing`]
+    /// for the forward search and [`Incoming`] for the backward one.
+    pub fn bidirectional_bfs(&self, start: TNode, goal: TNode) -> GraphSearchReport<TNode, TEdge> {
+        // search state for each direction, indexed by `Direction::index`
+        let mut parents = [HashMap::new(), HashMap::new()];
+        let mut depths = [HashMap::from([(start, 0)]), HashMap::from([(goal, 0)])];
+        let mut frontiers = [vec![start], vec![goal]];
+        let mut stats = [SearchDirection::default(), SearchDirection::default()];
+        stats[Outgoing.index()].generated.push(start);
+        stats[Incoming.index()].generated.push(goal);
+        let mut meeting = if start == goal { Some(start) } else { None };
+        while meeting.is_none()
+            && !frontiers[Outgoing.index()].is_empty()
+            && !frontiers[Incoming.index()].is_empty()
+        {
+            let dir = if frontiers[Outgoing.index()].len() <= frontiers[Incoming.index()].len() {
+                Outgoing
+            } else {
+                Incoming
+            };
+            let (this, other) = (dir.index(), dir.opposite().index());
+            let mut best: Option<(usize, TNode)> = None;
+            let mut next_frontier = Vec::new();
+            for node in std::mem::take(&mut frontiers[this]) {
+                stats[this].expanded.push(node);
+                let depth = depths[this][&node] + 1;
+                for neighbor in self.neighbors_directed(node, dir) {
+                    stats[this].generated.push(neighbor);
+                    if depths[this].contains_key(&neighbor) {
+                        continue;
+                    }
+                    depths[this].insert(neighbor, depth);
+                    parents[this].insert(neighbor, node);
+                    next_frontier.push(neighbor);
+                    if let Some(&other_depth) = depths[other].get(&neighbor) {
+                        if best.is_none_or(|(length, _)| depth + other_depth < length) {
+                            best = Some((depth + other_depth, neighbor));
+                        }
+                    }
+                }
+            }
+            frontiers[this] = next_frontier;
+            meeting = best.map(|(_, node)| node);
+        }
+        let [forward, backward] = stats;
+        let mut generated = forward.generated.clone();
+        generated.extend(&backward.generated);
+        let mut expanded = forward.expanded.clone();
+        expanded.extend(&backward.expanded);
+        let mut tree = HashMap::new();
+        if let Some(meeting) = meeting {
+            let mut node = meeting;
+            while let Some(&parent) = parents[Outgoing.index()].get(&node) {
+                tree.insert(node, parent);
+                node = parent;
+            }
+            let mut node = meeting;
+            while let Some(&next) = parents[Incoming.index()].get(&node) {
+                tree.insert(next, node);
+                node = next;
+            }
+        }
+        GraphSearchReport {
+            directions: Some([forward, backward]),
+            ..self.report(tree, start, goal, generated, expanded)
+        }
+    }
+
+    /// Computes the lowest path cost between every pair of nodes, using the Floyd-Warshall
+    /// algorithm.
+    ///
+    /// Edges may have negative weights, but if the graph has a cycle with a negative total
+    /// weight the lowest costs are not defined and the nodes of one such cycle are returned
+    /// as the error. On undirected graphs any edge with a negative weight is such a cycle.
+    pub fn floyd_warshall(&self) -> Result<DistanceMatrix<TNode, TEdge>, NegativeCycle<TNode>> {
+        let mut matrix = DistanceMatrix::new(self.nodes().collect());
+        let n = matrix.nodes.len();
+        for i in 0..n {
+            matrix.distances[i * n + i] = Some(TEdge::default());
+            matrix.next[i * n + i] = Some(i);
+        }
+        for (i, &node) in matrix.nodes.iter().enumerate() {
+            for (_, neighbor, &weight) in self.edges(node) {
+                let j = matrix.nodes.get_index_of(&neighbor).unwrap();
+                if matrix.distances[i * n + j].is_none_or(|old| weight < old) {
+                    matrix.distances[i * n + j] = Some(weight);
+                    matrix.next[i * n + j] = Some(j);
+                }
+            }
+        }
+        for k in 0..n {
+            for i in 0..n {
+                let Some(first) = matrix.distances[i * n + k] else {
+                    continue;
+                };
+                for j in 0..n {
+                    let Some(second) = matrix.distances[k * n + j] else {
+                        continue;
+                    };
+                    let cost = first + second;
+                    if matrix.distances[i * n + j].is_none_or(|old| cost < old) {
+                        matrix.distances[i * n + j] = Some(cost);
+                        matrix.next[i * n + j] = matrix.next[i * n + k];
+                    }
+                }
+            }
+        }
+        let zero = TEdge::default();
+        if (0..n).any(|i| matrix.distances[i * n + i].is_some_and(|cost| cost < zero)) {
+            return Err(self.negative_cycle());
+        }
+        Ok(matrix)
+    }
+
+    /// Computes the lowest path cost between every pair of nodes, using Johnson's algorithm.
+    ///
+    /// The edges are reweighted with the potentials computed by the Bellman-Ford algorithm
+    /// so that none of them is negative, and then Dijkstra's algorithm is run from every
+    /// node. This is faster than [`GraphMap::floyd_warshall`] on sparse graphs. Negative
+    /// cycles are reported in the same way.
+    pub fn johnson(&self) -> Result<DistanceMatrix<TNode, TEdge>, NegativeCycle<TNode>>
+    where
+        TEdge: std::ops::Sub<Output = TEdge>,
+    {
+        // relaxing from every node at once is the same as relaxing from a new node with an
+        // edge of weight zero to each of them
+        let potentials = self
+            .relax_from(self.nodes().map(|node| (node, TEdge::default())).collect())?
+            .distances;
+        let mut matrix = DistanceMatrix::new(self.nodes().collect());
+        let n = matrix.nodes.len();
+        for i in 0..n {
+            let start = matrix.nodes[i];
+            let (costs, hops) = self.reweighted_dijkstra(start, |from, to, weight| {
+                weight + potentials[&from] - potentials[&to]
+            });
+            for (node, cost) in costs {
+                let j = matrix.nodes.get_index_of(&node).unwrap();
+                matrix.distances[i * n + j] = Some(cost + potentials[&node] - potentials[&start]);
+                matrix.next[i * n + j] = matrix.nodes.get_index_of(&hops[&node]);
+            }
+        }
+        Ok(matrix)
+    }
+
+    /// Runs the Bellman-Ford algorithm from the nodes with a known cost in `distances`.
+    fn relax_from(
+        &self,
+        mut distances: HashMap<TNode, TEdge>,
+    ) -> Result<ShortestPathTree<TNode, TEdge>, NegativeCycle<TNode>> {
+        let mut parents = HashMap::new();
+        // after `n - 1` rounds every shortest path is known, so a relaxation in the
+        // `n`-th round means that there is a negative cycle
+        let mut relaxed = None;
+        for _ in 0..self.node_count() {
+            relaxed = None;
+            for node in self.nodes() {
+                let Some(&cost) = distances.get(&node) else {
+                    continue;
+                };
+                for (_, neighbor, &weight) in self.edges(node) {
+                    let next_cost = cost + weight;
+                    if distances.get(&neighbor).is_none_or(|&old| next_cost < old) {
+                        distances.insert(neighbor, next_cost);
+                        parents.insert(neighbor, node);
+                        relaxed = Some(neighbor);
+                    }
+                }
+            }
+            if relaxed.is_none() {
+                break;
+            }
+        }
+        let Some(mut node) = relaxed else {
+            return Ok(ShortestPathTree { distances, parents });
+        };
+        // walking back `n` parents from the last relaxed node always lands on the cycle
+        for _ in 0..self.node_count() {
+            node = parents[&node];
+        }
+        let mut cycle = vec![node];
+        let mut next = parents[&node];
+        while next != node {
+            cycle.push(next);
+            next = parents[&next];
+        }
+        cycle.reverse();
+        Err(NegativeCycle(cycle))
+    }
+
+    /// Returns a cycle with a negative total weight, assuming that the graph has one.
+    fn negative_cycle(&self) -> NegativeCycle<TNode> {
+        match self.relax_from(self.nodes().map(|node| (node, TEdge::default())).collect()) {
+            Err(cycle) => cycle,
+            Ok(_) => unreachable!("the graph has no negative cycle"),
+        }
+    }
+
+    /// Performs a graph search where the next node to expand is chosen by `policy`.
+    ///
+    /// This is [`graph_search`] on the problem of [`GraphMap::problem`].
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - The starting node for the search.
+    /// * `goal` - The goal node to search for.
+    /// * `policy` - How the next node to expand is chosen from the frontier.
+    /// * `seed` - The seed of the random number generator used by random policies.
+    pub fn search(
+        &self,
+        start: TNode,
+        goal: TNode,
+        policy: FrontierPolicy,
+        seed: u64,
+    ) -> GraphSearchReport<TNode, TEdge> {
+        graph_search(&self.problem(start, goal), policy, seed)
+    }
+
+    /// Performs the same search as [`GraphMap::search`] in trace mode, recording the state
+    /// of the search after every expansion in [`GraphSearchReport::trace`].
+    pub fn search_traced(
+        &self,
+        start: TNode,
+        goal: TNode,
+        policy: FrontierPolicy,
+        seed: u64,
+    ) -> GraphSearchReport<TNode, TEdge> {
+        FrontierSearch::new(self.problem(start, goal), policy, seed)
+            .with_trace()
+            .run()
+    }
+
+    /// Performs a graph search for the nodes that satisfy `goal`, where the next node to
+    /// expand is chosen by `policy`.
+    ///
+    /// The goals reached are recorded in [`GraphSearchReport::goals`], in the order they
+    /// were expanded. With [`GoalMode::First`] the search stops at the first goal, and with
+    /// [`GoalMode::All`] it goes on until the frontier is empty. The path and distance of
+    /// the report are the ones of the first goal, and the search tree contains the paths
+    /// to every goal reached.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - The starting node for the search.
+    /// * `goal` - The goal test, a closure or a set of goal nodes.
+    /// * `mode` - Whether to stop at the first goal or to reach all of them.
+    /// * `policy` - How the next node to expand is chosen from the frontier.
+    /// * `seed` - The seed of the random number generator used by random policies.
+    pub fn search_where<G>(
+        &self,
+        start: TNode,
+        goal: &G,
+        mode: GoalMode,
+        policy: FrontierPolicy,
+        seed: u64,
+    ) -> GraphSearchReport<TNode, TEdge>
+    where
+        G: Goal<TNode> + ?Sized,
+    {
+        self.multi_source_search([(start, TEdge::default())], goal, mode, policy, seed)
+    }
+
+    /// Performs a breadth-first search on the graph, starting from all the given sources at
+    /// once and searching for the nodes that satisfy `goal`.
+    ///
+    /// See [`GraphMap::multi_source_search`] for the details.
+    pub fn multi_source_bfs<I, G>(
+        &self,
+        sources: I,
+        goal: &G,
+        mode: GoalMode,
+    ) -> GraphSearchReport<TNode, TEdge>
+    where
+        I: IntoIterator<Item = (TNode, TEdge)>,
+        G: Goal<TNode> + ?Sized,
+    {
+        self.multi_source_search(sources, goal, mode, FrontierPolicy::Fifo, 0)
+    }
+
+    /// Performs a depth-first search on the graph, starting from all the given sources at
+    /// once and searching for the nodes that satisfy `goal`.
+    ///
+    /// See [`GraphMap::multi_source_search`] for the details.
+    pub fn multi_source_dfs<I, G>(
+        &self,
+        sources: I,
+        goal: &G,
+        mode: GoalMode,
+    ) -> GraphSearchReport<TNode, TEdge>
+    where
+        I: IntoIterator<Item = (TNode, TEdge)>,
+        G: Goal<TNode> + ?Sized,
+    {
+        self.multi_source_search(sources, goal, mode, FrontierPolicy::Lifo, 0)
+    }
+
+    /// Performs a graph search that starts from several sources at once, each one reached
+    /// with its own initial cost, and searches for the nodes that satisfy `goal`.
+    ///
+    /// The frontier is seeded with every source in the given order, a source repeated
+    /// keeps its first cost, and the search then goes on as [`GraphMap::search_where`].
+    /// When there is more than one distinct source, the source each reached node
+    /// originates from is recorded in [`GraphSearchReport::origins`], so a search with
+    /// [`GoalMode::All`] and a goal that never holds partitions the reachable nodes into
+    /// the regions of their sources. The distance of the report includes the initial cost
+    /// of the source of the first goal.
+    ///
+    /// With [`FrontierPolicy::LowestCost`] the origin of a node in the frontier changes
+    /// along with its cost when a cheaper path to it is found, so every node is assigned to
+    /// its closest source, as in [`GraphMap::multi_source_ucs`].
+    ///
+    /// # Arguments
+    ///
+    /// * `sources` - The starting nodes for the search, with their initial costs.
+    /// * `goal` - The goal test, a closure or a set of goal nodes.
+    /// * `mode` - Whether to stop at the first goal or to reach all of them.
+    /// * `policy` - How the next node to expand is chosen from the frontier.
+    /// * `seed` - The seed of the random number generator used by random policies.
+    pub fn multi_source_search<I, G>(
+        &self,
+        sources: I,
+        goal: &G,
+        mode: GoalMode,
+        policy: FrontierPolicy,
+        seed: u64,
+    ) -> GraphSearchReport<TNode, TEdge>
+    where
+        I: IntoIterator<Item = (TNode, TEdge)>,
+        G: Goal<TNode> + ?Sized,
+    {
+        let sources: Vec<_> = sources.into_iter().collect();
+        let Some(&(start, _)) = sources.first() else {
+            return GraphSearchReport {
+                seed: policy.is_random().then_some(seed),
+                ..Default::default()
+            };
+        };
+        let problem = GraphProblem::new(self, start, |node| goal.is_goal(node));
+        FrontierSearch::from_sources(problem, sources, policy, seed)
+            .with_goal_mode(mode)
+            .run()
+    }
+
+    /// Performs a uniform-cost search on the graph, starting from all the given sources at
+    /// once and searching for the nodes that satisfy `goal`.
+    ///
+    /// This is [`multi_source_uniform_cost_search`] on the graph. Every node reached is
+    /// assigned to the source with the lowest total cost to it, initial cost included, so
+    /// a search with [`GoalMode::All`] and a goal that never holds partitions the reachable
+    /// nodes into the regions of their closest sources.
+    ///
+    /// # Arguments
+    ///
+    /// * `sources` - The starting nodes for the search, with their initial costs.
+    /// * `goal` - The goal test, a closure or a set of goal nodes.
+    /// * `mode` - Whether to stop at the first goal or to reach all of them.
+    pub fn multi_source_ucs<I, G>(
+        &self,
+        sources: I,
+        goal: &G,
+        mode: GoalMode,
+    ) -> GraphSearchReport<TNode, TEdge>
+    where
+        I: IntoIterator<Item = (TNode, TEdge)>,
+        G: Goal<TNode> + ?Sized,
+    {
+        let sources: Vec<_> = sources.into_iter().collect();
+        let Some(&(start, _)) = sources.first() else {
+            return GraphSearchReport::default();
+        };
+        let problem = GraphProblem::new(self, start, |node| goal.is_goal(node));
+        multi_source_uniform_cost_search(&problem, sources, mode)
+    }
+
+    /// Builds the report of a search that stopped with the given search tree.
+    ///
+    /// The goal is reached if it is the start or if it has a parent in the tree. Every
+    /// edge of the tree must be in the graph.
+    fn report(
+        &self,
+        parents: HashMap<TNode, TNode>,
+        start: TNode,
+        goal: TNode,
+        generated: Vec<TNode>,
+        expanded: Vec<TNode>,
+    ) -> GraphSearchReport<TNode, TEdge> {
+        let tree = parents
+            .iter()
+            .map(|(&node, &parent)| {
+                let weight = self
+                    .get_edge(parent, node)
+                    .expect("tree edge not in the graph");
+                (node, (parent, *weight))
+            })
+            .collect();
+        let reached = goal == start || parents.contains_key(&goal);
+        let mut report = GraphSearchReport::from_tree(
+            tree,
+            reached.then_some((goal, TEdge::default())),
+            generated,
+            expanded,
+        );
+        if report.path.is_some() {
+            report.distance = Some(
+                report
+                    .path_weights
+                    .iter()
+                    .fold(TEdge::default(), |acc, &weight| acc + weight),
+            );
+        }
+        report
+    }
+}
+
+impl<TNode, TEdge> GraphMap<TNode, TEdge, Directed>
+where
+    TNode: NodeTrait + std::fmt::Debug,
+    TEdge: Default + Copy + PartialOrd + std::ops::Add<Output = TEdge> + std::fmt::Debug,
+{
+    /// Computes the lowest path cost from `start` to every node reachable from it, using the
+    /// Bellman-Ford algorithm.
+    ///
+    /// Unlike [`GraphMap::dijkstra`], edges may have negative weights. If a cycle with a
+    /// negative total weight is reachable from `start` the lowest costs are not defined, and
+    /// the nodes of one such cycle are returned as the error.
+    pub fn bellman_ford(
+        &self,
+        start: TNode,
+    ) -> Result<ShortestPathTree<TNode, TEdge>, NegativeCycle<TNode>> {
+        self.relax_from(HashMap::from([(start, TEdge::default())]))
+    }
+}
+
+impl<TNode, TEdge, Ty> GraphMap<TNode, TEdge, Ty>
+where
+    TNode: NodeTrait,
+    Ty: EdgeType,
+{
+    /// Returns the adjacency list of a node, or an empty one if the node is missing.
+    fn links(&self, node: TNode) -> &[(TNode, CompactDirection)] {
+        self.nodes.get(&node).map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns the key used to store the edge between `a` and `b` in `edges`.
+    ///
+    /// Undirected edges are normalized so that the smallest node comes first.
+    #[inline]
+    fn edge_key(a: TNode, b: TNode) -> (TNode, TNode) {
+        if Ty::is_directed() || a <= b {
+            (a, b)
+        } else {
+            (b, a)
+        }
+    }
+}
+
+impl<TNode, TEdge, Ty> Default for GraphMap<TNode, TEdge, Ty>
+where
+    TNode: NodeTrait,
+{
+    /// Creates an empty [`GraphMap<TNode, TEdge, Ty>`].
+    fn default() -> Self {
+        Self {
+            nodes: IndexMap::new(),
+            edges: IndexMap::new(),
+            ty: PhantomData,
+        }
+    }
+}
+#[derive(Clone, Debug)]
+pub struct GraphSearchReport<TNode, TEdge> {
+    /// The nodes of the path found, in order from the start to the goal, or `None` if no
+    /// goal was reached.
+    pub path: Option<Vec<TNode>>,
+    /// The weights of the edges of the path found, in order, so the edge between
+    /// `path[i]` and `path[i + 1]` weighs `path_weights[i]`.
+    pub path_weights: Vec<TEdge>,
+    pub distance: Option<TEdge>,
+    pub generated_nodes: Vec<TNode>,
+    pub expanded_nodes: Vec<TNode>,
+    /// The seed of the random number generator, if the search made random choices.
+    pub seed: Option<u64>,
+    /// How the search ended.
+    pub status: SearchStatus,
+    /// The goals reached, in the order they were found.
+    pub goals: Vec<TNode>,
+    /// The source each node reached by the search originates from, for the searches that
+    /// start from more than one distinct source. It is empty for every other search.
+    pub origins: HashMap<TNode, TNode>,
+    /// The parent of every node in the tree built by the search, which contains the path
+    /// found if any.
+    pub search_tree: HashMap<TNode, TNode>,
+    /// The statistics of each iteration, for the searches that run several bounded
+    /// iterations.
+    pub iterations: Vec<SearchIteration<TEdge>>,
+    /// The nodes generated and expanded in each direction, for the searches that run both
+    /// forward and backward, indexed by [`Direction::index`].
+    pub directions: Option<[SearchDirection<TNode>; 2]>,
+    /// The state of the search after every expansion, for the searches run in trace mode.
+    pub trace: Vec<SearchSnapshot<TNode, TEdge>>,
+}
+
+impl<TNode, TEdge> Default for GraphSearchReport<TNode, TEdge> {
+    /// Creates the report of a failed search that did nothing.
+    fn default() -> Self {
+        Self {
+            path: None,
+            path_weights: Vec::new(),
+            distance: None,
+            generated_nodes: Vec::new(),
+            expanded_nodes: Vec::new(),
+            seed: None,
+            status: SearchStatus::Failure,
+            goals: Vec::new(),
+            origins: HashMap::new(),
+            search_tree: HashMap::new(),
+            iterations: Vec::new(),
+            directions: None,
+            trace: Vec::new(),
+        }
+    }
+}
+
+impl<TNode, TEdge> GraphSearchReport<TNode, TEdge>
+where
+    TNode: NodeTrait,
+    TEdge: Copy,
+{
+    /// Builds the report of a search that stopped with the given search tree, where the
+    /// parent of every node is stored with the weight of the edge from it, and `goal` is
+    /// the goal reached, if any, with its path cost.
+    ///
+    /// A goal whose path in the tree has a cycle is not reported as reached.
+    pub(crate) fn from_tree(
+        parents: HashMap<TNode, (TNode, TEdge)>,
+        goal: Option<(TNode, TEdge)>,
+        generated: Vec<TNode>,
+        expanded: Vec<TNode>,
+    ) -> Self {
+        let search_tree = parents
+            .iter()
+            .map(|(&node, &(parent, _))| (node, parent))
+            .collect();
+        let mut report = GraphSearchReport {
+            generated_nodes: generated,
+            expanded_nodes: expanded,
+            search_tree,
+            ..Default::default()
+        };
+        let Some((goal, distance)) = goal else {
+            return report;
+        };
+        let mut path = vec![goal];
+        let mut weights = Vec::new();
+        while let Some(&(parent, weight)) = parents.get(&path[path.len() - 1]) {
+            if path.len() > parents.len() {
+                // a tree with n parents has paths of at most n + 1 nodes
+                return report;
+            }
+            weights.push(weight);
+            path.push(parent);
+        }
+        path.reverse();
+        weights.reverse();
+        report.path = Some(path);
+        report.path_weights = weights;
+        report.distance = Some(distance);
+        report.status = SearchStatus::Found;
+        report.goals = vec![goal];
+        report
+    }
+}
+
+impl<TNode, TEdge> GraphSearchReport<TNode, TEdge> {
+    /// Returns the nodes of the path found, in order from the start to the goal.
+    pub fn path_nodes(&self) -> Option<Vec<TNode>>
+    where
+        TNode: Copy,
+    {
+        self.path.clone()
+    }
+
+    /// Returns the edges of the path found as `(source, target, weight)`, in order from
+    /// the start to the goal. The path of a search whose start is a goal has no edges.
+    pub fn path_edges(&self) -> Option<Vec<(TNode, TNode, TEdge)>>
+    where
+        TNode: Copy,
+        TEdge: Copy,
+    {
+        let path = self.path.as_ref()?;
+        Some(
+            path.windows(2)
+                .zip(&self.path_weights)
+                .map(|(pair, &weight)| (pair[0], pair[1], weight))
+                .collect(),
+        )
+    }
+
+    /// Returns the path found as its nodes joined by arrows, or an empty string if no goal
+    /// was reached.
+    pub fn str_path(&self) -> String
+    where
+        TNode: std::fmt::Display,
+    {
+        let Some(path) = &self.path else {
+            return String::new();
+        };
+        path.iter()
+            .map(|node| node.to_string())
+            .collect::<Vec<_>>()
+            .join(" -> ")
+    }
+}
+
+/// The lowest path costs from a start node, together with the tree of the paths that
+/// achieve them.
+#[derive(Clone, Debug)]
+pub struct ShortestPathTree<TNode, TEdge> {
+    /// The lowest path cost of every node reachable from the start.
+    pub distances: HashMap<TNode, TEdge>,
+    /// The parent of every node reachable from the start, except the start itself.
+    pub parents: HashMap<TNode, TNode>,
+}
+
+/// The lowest path cost between every pair of nodes of a graph, together with the next
+/// node of the paths that achieve them.
+#[derive(Clone, Debug)]
+pub struct DistanceMatrix<TNode, TEdge> {
+    nodes: IndexSet<TNode>,
+    /// Row-major `n x n` matrix of costs, `None` if there is no path.
+    distances: Vec<Option<TEdge>>,
+    /// Row-major `n x n` matrix of indices of the next node of each path.
+    next: Vec<Option<usize>>,
+}
+
+impl<TNode, TEdge> DistanceMatrix<TNode, TEdge>
+where
+    TNode: NodeTrait,
+    TEdge: Copy,
+{
+    /// Creates a matrix where no node is reachable from any other.
+    fn new(nodes: IndexSet<TNode>) -> Self {
+        let n = nodes.len();
+        Self {
+            nodes,
+            distances: vec![None; n * n],
+            next: vec![None; n * n],
+        }
+    }
+
+    /// Returns the nodes of the matrix, in the order of its rows and columns.
+    pub fn nodes(&self) -> &IndexSet<TNode> {
+        &self.nodes
+    }
+
+    fn index(&self, from: TNode, to: TNode) -> Option<usize> {
+        let i = self.nodes.get_index_of(&from)?;
+        let j = self.nodes.get_index_of(&to)?;
+        Some(i * self.nodes.len() + j)
+    }
+
+    /// Returns the lowest path cost from `from` to `to`, or `None` if there is no path.
+    pub fn distance(&self, from: TNode, to: TNode) -> Option<TEdge> {
+        self.distances[self.index(from, to)?]
+    }
+
+    /// Returns the node that follows `from` in the lowest cost path from `from` to `to`.
+    ///
+    /// The next hop from a node to itself is the node itself.
+    pub fn next_hop(&self, from: TNode, to: TNode) -> Option<TNode> {
+        let next = self.next[self.index(from, to)?]?;
+        self.nodes.get_index(next).copied()
+    }
+
+    /// Returns the nodes of the lowest cost path from `from` to `to`, both included.
+    pub fn path(&self, from: TNode, to: TNode) -> Option<Vec<TNode>> {
+        let mut path = vec![from];
+        let mut node = from;
+        while node != to {
+            node = self.next_hop(node, to)?;
+            path.push(node);
+            if path.len() > self.nodes.len() {
+                return None;
+            }
+        }
+        Some(path)
+    }
+}
+
+/// A cycle with a negative total weight, given as the nodes it visits in order.
+///
+/// The cycle goes back from the last node to the first one.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NegativeCycle<TNode>(pub Vec<TNode>);
+
+impl<TNode: fmt::Debug> fmt::Display for NegativeCycle<TNode> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "negative cycle:")?;
+        for node in &self.0 {
+            write!(f, " {:?} ->", node)?;
+        }
+        match self.0.first() {
+            Some(first) => write!(f, " {:?}", first),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<TNode: fmt::Debug> std::error::Error for NegativeCycle<TNode> {}
+
+/// How a search ended.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SearchStatus {
+    /// The goal was found.
+    Found,
+    /// The goal was not found, but a bound prevented exploring part of the graph.
+    Cutoff,
+    /// The goal is not reachable from the start.
+    Failure,
+}
+
+impl fmt::Display for SearchStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SearchStatus::Found => write!(f, "found"),
+            SearchStatus::Cutoff => write!(f, "cutoff"),
+            SearchStatus::Failure => write!(f, "failure"),
+        }
+    }
+}
+
+/// The bound of an iteration of a bounded search.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SearchBound<TEdge> {
+    /// The maximum number of edges from the start.
+    Depth(usize),
+    /// The maximum estimated cost of a path.
+    Cost(TEdge),
+}
+
+impl<TEdge: fmt::Display> fmt::Display for SearchBound<TEdge> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SearchBound::Depth(depth) => write!(f, "depth {}", depth),
+            SearchBound::Cost(cost) => write!(f, "cost {}", cost),
+        }
+    }
+}
+
+/// The statistics of one iteration of a bounded search.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SearchIteration<TEdge> {
+    pub bound: SearchBound<TEdge>,
+    /// The number of nodes generated during the iteration.
+    pub generated: usize,
+    /// The number of nodes expanded during the iteration.
+    pub expanded: usize,
+    pub status: SearchStatus,
+}
+
+/// The nodes generated and expanded by one direction of a bidirectional search.
+#[derive(Clone, Debug)]
+pub struct SearchDirection<TNode> {
+    pub generated: Vec<TNode>,
+    pub expanded: Vec<TNode>,
+}
+
+impl<TNode> Default for SearchDirection<TNode> {
+    fn default() -> Self {
+        Self {
+            generated: Vec::new(),
+            expanded: Vec::new(),
+        }
+    }
+}
+
+/// The state of a search right after one of its expansions.
+#[derive(Clone, Debug)]
+pub struct SearchSnapshot<TNode, TEdge> {
+    /// The number of the iteration, starting at 1.
+    pub iteration: usize,
+    /// The node expanded in the iteration.
+    pub expanded: TNode,
+    /// The nodes of the frontier and their path costs after the expansion, oldest first.
+    pub frontier: Vec<(TNode, TEdge)>,
+    /// The number of nodes generated so far, including the repeated ones, so the nodes
+    /// themselves are the first ones of [`GraphSearchReport::generated_nodes`].
+    pub generated: usize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CompactDirection {
+    Outgoing,
+    Incoming,
+}
+
+impl CompactDirection {
+    /// Return the opposite `CompactDirection`.
+    #[inline]
+    pub fn opposite(self) -> CompactDirection {
+        match self {
+            CompactDirection::Outgoing => CompactDirection::Incoming,
+            CompactDirection::Incoming => CompactDirection::Outgoing,
+        }
+    }
+}
+
+impl From<CompactDirection> for Direction {
+    fn from(dir: CompactDirection) -> Self {
+        match dir {
+            CompactDirection::Outgoing => Outgoing,
+            CompactDirection::Incoming => Incoming,
+        }
+    }
+}
+
+impl From<Direction> for CompactDirection {
+    fn from(dir: Direction) -> Self {
+        match dir {
+            Outgoing => CompactDirection::Outgoing,
+            Incoming => CompactDirection::Incoming,
+        }
+    }
+}
+
+impl PartialEq<Direction> for CompactDirection {
+    fn eq(&self, rhs: &Direction) -> bool {
+        (*self as usize) == (*rhs as usize)
+    }
+}
+
+iterator_wrap!(
+    impl (Iterator DoubleEndedIterator ExactSizeIterator) for
+    #[derive(Clone, Debug)]
+    struct Nodes<'a, TNode> where { TNode: 'a + NodeTrait }
+    item: TNode,
+    iter: Cloned<Keys<'a, TNode, Vec<(TNode, CompactDirection)>>>,
+);
+
+#[derive(Clone, Debug)]
+pub struct Neighbors<'a, TNode, Ty = Undirected>
+where
+    TNode: 'a,
+    Ty: EdgeType,
+{
+    iter: Iter<'a, (TNode, CompactDirection)>,
     ty: PhantomData<Ty>,
 }
 
@@ -428,3 +1761,767 @@ where
         }
     }
 }
+
+/// An iterator over the simple paths between two nodes, see
+/// [`GraphMap::all_simple_paths`].
+#[derive(Clone)]
+pub struct AllSimplePaths<'a, TNode, TEdge, Ty = Undirected>
+where
+    TNode: 'a + NodeTrait,
+    TEdge: 'a,
+    Ty: EdgeType,
+{
+    graph: &'a GraphMap<TNode, TEdge, Ty>,
+    goal: TNode,
+    min_len: usize,
+    max_len: usize,
+    /// The nodes of the current path.
+    visited: IndexSet<TNode>,
+    /// The distance from the start to each node of the current path.
+    costs: Vec<TEdge>,
+    /// The edges left to try of each node of the current path.
+    stack: Vec<Edges<'a, TNode, TEdge, Ty>>,
+    /// The path without edges, when the start is the goal.
+    trivial: Option<(Vec<TNode>, TEdge)>,
+}
+
+impl<'a, TNode, TEdge, Ty> Iterator for AllSimplePaths<'a, TNode, TEdge, Ty>
+where
+    TNode: NodeTrait,
+    TEdge: Copy + std::ops::Add<Output = TEdge>,
+    Ty: EdgeType,
+{
+    type Item = (Vec<TNode>, TEdge);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(path) = self.trivial.take() {
+            return Some(path);
+        }
+        while let Some(edges) = self.stack.last_mut() {
+            let Some((_, node, &weight)) = edges.next() else {
+                self.stack.pop();
+                self.visited.pop();
+                self.costs.pop();
+                continue;
+            };
+            // the number of edges and the distance of the current path extended with `node`
+            let len = self.visited.len();
+            let cost = self.costs[len - 1] + weight;
+            if node == self.goal {
+                if len >= self.min_len && len <= self.max_len {
+                    let mut path: Vec<TNode> = self.visited.iter().copied().collect();
+                    path.push(node);
+                    return Some((path, cost));
+                }
+            } else if len < self.max_len && self.visited.insert(node) {
+                self.costs.push(cost);
+                self.stack.push(Edges {
+                    from: node,
+                    edges: &self.graph.edges,
+                    iter: self.graph.links(node).iter(),
+                    ty: PhantomData,
+                });
+            }
+        }
+        None
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct NeighborsDirected<'a, TNode, Ty = Undirected>
+where
+    TNode: 'a,
+    Ty: EdgeType,
+{
+    from: TNode,
+    iter: Iter<'a, (TNode, CompactDirection)>,
+    dir: Direction,
+    ty: PhantomData<Ty>,
+}
+
+impl<'a, TNode, Ty> Iterator for NeighborsDirected<'a, TNode, Ty>
+where
+    TNode: NodeTrait,
+    Ty: EdgeType,
+{
+    type Item = TNode;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if Ty::is_directed() {
+            let (from, dir) = (self.from, self.dir);
+            // self loops are only stored once, as outgoing links
+            (&mut self.iter)
+                .filter_map(|&(n, link_dir)| {
+                    if link_dir == dir || n == from {
+                        Some(n)
+                    } else {
+                        None
+                    }
+                })
+                .next()
+        } else {
+            self.iter.next().map(|&(n, _)| n)
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.iter.size_hint();
+        if Ty::is_directed() {
+            (0, upper)
+        } else {
+            (lower, upper)
+        }
+    }
+}
+
+iterator_wrap!(
+    impl (Iterator DoubleEndedIterator ExactSizeIterator) for
+    #[derive(Clone, Debug)]
+    struct AllEdges<'a, TNode, TEdge> where { TNode: 'a + NodeTrait, TEdge: 'a }
+    item: (TNode, TNode, &'a TEdge),
+    iter: Map<
+        indexmap::map::Iter<'a, (TNode, TNode), TEdge>,
+        fn((&'a (TNode, TNode), &'a TEdge)) -> (TNode, TNode, &'a TEdge),
+    >,
+);
+
+// `Edges` and `EdgesDirected` are written out by hand instead of going through
+// `iterator_wrap!`: their adaptors capture `from`, `dir` and the edge map in
+// closures, and a closure type cannot be named in the wrapped `iter` field.
+// Keeping the adjacency iterator plus that state lets us filter and look up
+// weights lazily in `next`.
+#[derive(Clone, Debug)]
+pub struct Edges<'a, TNode, TEdge, Ty = Undirected>
+where
+    TNode: 'a + NodeTrait,
+    TEdge: 'a,
+    Ty: EdgeType,
+{
+    from: TNode,
+    edges: &'a IndexMap<(TNode, TNode), TEdge>,
+    iter: Iter<'a, (TNode, CompactDirection)>,
+    ty: PhantomData<Ty>,
+}
+
+impl<'a, TNode, TEdge, Ty> Iterator for Edges<'a, TNode, TEdge, Ty>
+where
+    TNode: 'a + NodeTrait,
+    TEdge: 'a,
+    Ty: EdgeType,
+{
+    type Item = (TNode, TNode, &'a TEdge);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let from = self.from;
+        let edges = self.edges;
+        (&mut self.iter)
+            .filter(|&&(_, dir)| !Ty::is_directed() || dir == Outgoing)
+            .map(|&(to, _)| {
+                let key = GraphMap::<TNode, TEdge, Ty>::edge_key(from, to);
+                (from, to, &edges[&key])
+            })
+            .next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.iter.size_hint();
+        if Ty::is_directed() {
+            (0, upper)
+        } else {
+            (lower, upper)
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct EdgesDirected<'a, TNode, TEdge, Ty = Undirected>
+where
+    TNode: 'a + NodeTrait,
+    TEdge: 'a,
+    Ty: EdgeType,
+{
+    from: TNode,
+    dir: Direction,
+    edges: &'a IndexMap<(TNode, TNode), TEdge>,
+    iter: Iter<'a, (TNode, CompactDirection)>,
+    ty: PhantomData<Ty>,
+}
+
+impl<'a, TNode, TEdge, Ty> Iterator for EdgesDirected<'a, TNode, TEdge, Ty>
+where
+    TNode: 'a + NodeTrait,
+    TEdge: 'a,
+    Ty: EdgeType,
+{
+    type Item = (TNode, TNode, &'a TEdge);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let from = self.from;
+        let dir = self.dir;
+        let edges = self.edges;
+        (&mut self.iter)
+            .filter(|&&(other, link_dir)| !Ty::is_directed() || link_dir == dir || other == from)
+            .map(|&(other, _)| {
+                let (a, b) = if Ty::is_directed() && dir == Incoming {
+                    (other, from)
+                } else {
+                    (from, other)
+                };
+                let key = GraphMap::<TNode, TEdge, Ty>::edge_key(a, b);
+                (a, b, &edges[&key])
+            })
+            .next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.iter.size_hint();
+        if Ty::is_directed() {
+            (0, upper)
+        } else {
+            (lower, upper)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Asserts that the adjacency lists and the edge map describe the same edges: every
+    /// edge has one outgoing link at its source and, unless it is a self-loop, one incoming
+    /// link at its target, and there are no other links. The source of an undirected edge
+    /// is the endpoint given first when it was added.
+    fn assert_consistent<Ty: EdgeType>(graph: &GraphMap<usize, f64, Ty>) {
+        let links = |node: usize, other: usize| -> Vec<CompactDirection> {
+            graph.nodes[&node]
+                .iter()
+                .filter(|&&(next, _)| next == other)
+                .map(|&(_, dir)| dir)
+                .collect()
+        };
+        let mut count = 0;
+        for &(a, b) in graph.edges.keys() {
+            assert_eq!(GraphMap::<usize, f64, Ty>::edge_key(a, b), (a, b));
+            if a == b {
+                assert_eq!(
+                    links(a, a),
+                    vec![CompactDirection::Outgoing],
+                    "edge ({a}, {a})"
+                );
+                count += 1;
+                continue;
+            }
+            let (forward, backward) = (links(a, b), links(b, a));
+            if Ty::is_directed() {
+                assert!(
+                    forward.contains(&CompactDirection::Outgoing),
+                    "edge ({a}, {b})"
+                );
+                assert!(
+                    backward.contains(&CompactDirection::Incoming),
+                    "edge ({a}, {b})"
+                );
+            } else {
+                assert_eq!(forward.len(), 1, "edge ({a}, {b})");
+                assert_eq!(backward, vec![forward[0].opposite()], "edge ({a}, {b})");
+            }
+            count += 2;
+        }
+        assert_eq!(graph.nodes.values().map(Vec::len).sum::<usize>(), count);
+    }
+
+    #[test]
+    fn remove_node_keeps_directed_adjacency_consistent() {
+        let mut graph = GraphMap::<usize, f64, Directed>::new();
+        graph.add_edge(1, 2, 1.0);
+        graph.add_edge(2, 1, 2.0);
+        graph.add_edge(2, 3, 3.0);
+        graph.add_edge(3, 3, 4.0);
+        graph.add_edge(3, 1, 5.0);
+        assert_consistent(&graph);
+
+        assert!(graph.remove_node(1));
+        assert!(!graph.remove_node(1));
+        assert_consistent(&graph);
+        assert_eq!(graph.edge_count(), 2);
+        assert!(graph.contains_edge(2, 3));
+        assert!(graph.contains_edge(3, 3));
+
+        assert!(graph.remove_node(3));
+        assert_consistent(&graph);
+        assert_eq!(graph.edge_count(), 0);
+        assert_eq!(graph.nodes().collect::<Vec<_>>(), vec![2]);
+    }
+
+    #[test]
+    fn remove_node_keeps_undirected_adjacency_consistent() {
+        let mut graph = GraphMap::<usize, f64, Undirected>::new();
+        graph.add_edge(2, 1, 1.0);
+        graph.add_edge(1, 3, 2.0);
+        graph.add_edge(3, 2, 3.0);
+        graph.add_edge(1, 1, 4.0);
+        assert_consistent(&graph);
+
+        assert!(graph.remove_node(1));
+        assert_consistent(&graph);
+        assert_eq!(graph.edge_count(), 1);
+        assert_eq!(graph.get_edge(2, 3), Some(&3.0));
+        assert_eq!(graph.neighbors(2).collect::<Vec<_>>(), vec![3]);
+    }
+
+    #[test]
+    fn remove_edge_keeps_adjacency_consistent() {
+        let mut graph = GraphMap::<usize, f64, Directed>::new();
+        graph.add_edge(1, 2, 1.0);
+        graph.add_edge(2, 1, 2.0);
+        graph.add_edge(1, 1, 3.0);
+
+        assert_eq!(graph.remove_edge(&1, &2), Some(1.0));
+        assert_eq!(graph.remove_edge(&1, &2), None);
+        assert_consistent(&graph);
+        assert_eq!(graph.get_edge(2, 1), Some(&2.0));
+        assert_eq!(graph.neighbors(1).collect::<Vec<_>>(), vec![1]);
+        assert_eq!(graph.neighbors(2).collect::<Vec<_>>(), vec![1]);
+
+        assert_eq!(graph.remove_edge(&1, &1), Some(3.0));
+        assert_consistent(&graph);
+        assert_eq!(graph.neighbors(1).count(), 0);
+
+        let mut graph = GraphMap::<usize, f64, Undirected>::new();
+        graph.add_edge(1, 2, 1.0);
+        graph.add_edge(3, 2, 2.0);
+        // the endpoints of an undirected edge can be given in either order
+        assert_eq!(graph.remove_edge(&2, &1), Some(1.0));
+        assert_consistent(&graph);
+        assert_eq!(graph.remove_edge(&2, &3), Some(2.0));
+        assert_consistent(&graph);
+        assert_eq!(graph.edge_count(), 0);
+        assert_eq!(graph.node_count(), 3);
+    }
+
+    #[test]
+    fn remove_single_edge_only_touches_one_adjacency_list() {
+        let mut graph = GraphMap::<usize, f64, Directed>::new();
+        graph.add_edge(1, 2, 1.0);
+        graph.add_edge(2, 1, 2.0);
+        // the incoming link of 1 from 2 stays while its outgoing link to 2 goes
+        assert!(graph.remove_single_edge(&1, &2, CompactDirection::Outgoing));
+        assert!(!graph.remove_single_edge(&1, &2, CompactDirection::Outgoing));
+        assert_eq!(graph.nodes[&1], vec![(2, CompactDirection::Incoming)]);
+        assert_eq!(graph.edge_count(), 2);
+        assert!(!graph.remove_single_edge(&4, &1, CompactDirection::Outgoing));
+
+        let mut graph = GraphMap::<usize, f64, Undirected>::new();
+        graph.add_edge(1, 2, 1.0);
+        // undirected graphs ignore the direction of the link
+        assert!(graph.remove_single_edge(&2, &1, CompactDirection::Outgoing));
+        assert!(graph.nodes[&2].is_empty());
+        assert_eq!(graph.nodes[&1], vec![(2, CompactDirection::Outgoing)]);
+    }
+
+    #[test]
+    fn undirected_edges_are_the_same_in_both_directions() {
+        let mut graph = GraphMap::<usize, f64, Undirected>::new();
+        assert_eq!(graph.add_edge(3, 1, 1.0), None);
+        assert_eq!(graph.add_edge(1, 3, 2.0), Some(1.0));
+        assert_eq!(graph.add_edge(3, 1, 3.0), Some(2.0));
+        assert_consistent(&graph);
+        assert_eq!(graph.edge_count(), 1);
+        assert_eq!(graph.nodes[&1].len(), 1);
+        assert_eq!(graph.nodes[&3].len(), 1);
+        assert_eq!(graph.get_edge(1, 3), Some(&3.0));
+        assert_eq!(graph.get_edge(3, 1), Some(&3.0));
+        assert!(graph.contains_edge(1, 3));
+    }
+
+    #[test]
+    fn directed_edges_have_a_direction() {
+        let mut graph = GraphMap::<usize, f64, Directed>::new();
+        graph.add_edge(3, 1, 1.0);
+        assert_eq!(graph.get_edge(3, 1), Some(&1.0));
+        assert_eq!(graph.get_edge(1, 3), None);
+        assert!(graph.contains_edge(3, 1));
+        assert!(!graph.contains_edge(1, 3));
+
+        assert_eq!(graph.add_edge(1, 3, 2.0), None);
+        assert_consistent(&graph);
+        assert_eq!(graph.edge_count(), 2);
+        assert_eq!(graph.get_edge(1, 3), Some(&2.0));
+        assert_eq!(graph.get_edge(3, 1), Some(&1.0));
+    }
+
+    #[test]
+    fn multi_source_origins_follow_the_cheapest_path() {
+        let mut graph = GraphMap::<usize, f64, Undirected>::new();
+        graph.add_edge(1, 2, 10.0);
+        graph.add_edge(4, 3, 1.0);
+        graph.add_edge(3, 2, 1.0);
+        let sources = [(1, 0.0), (4, 0.0)];
+        let never = |_: usize| false;
+        let ucs = graph.multi_source_ucs(sources, &never, GoalMode::All);
+        let search = graph.multi_source_search(
+            sources,
+            &never,
+            GoalMode::All,
+            FrontierPolicy::LowestCost,
+            0,
+        );
+        for report in [&ucs, &search] {
+            assert_eq!(report.origins[&1], 1);
+            assert_eq!(report.origins[&2], 4);
+            assert_eq!(report.origins[&3], 4);
+            assert_eq!(report.origins[&4], 4);
+            assert_eq!(report.search_tree[&2], 3);
+        }
+
+        let report = graph.multi_source_ucs(sources, &|node| node == 2, GoalMode::First);
+        assert_eq!(report.path, Some(vec![4, 3, 2]));
+        assert_eq!(report.distance, Some(2.0));
+
+        // the initial costs count towards the closest source
+        let report = graph.multi_source_ucs([(1, 0.0), (4, 9.5)], &never, GoalMode::All);
+        assert_eq!(report.origins[&2], 1);
+        assert_eq!(report.origins[&3], 4);
+    }
+
+    #[test]
+    fn lowest_cost_search_lowers_the_cost_of_frontier_nodes() {
+        let mut graph = GraphMap::<usize, f64, Undirected>::new();
+        graph.add_edge(1, 2, 10.0);
+        graph.add_edge(1, 3, 1.0);
+        graph.add_edge(3, 2, 1.0);
+        let report = graph.search(1, 2, FrontierPolicy::LowestCost, 0);
+        assert_eq!(report.path, Some(vec![1, 3, 2]));
+        assert_eq!(report.distance, Some(2.0));
+        assert_eq!(report.path_weights, vec![1.0, 1.0]);
+        let ucs = graph.ucs(1, 2);
+        assert_eq!(report.path, ucs.path);
+        assert_eq!(report.distance, ucs.distance);
+
+        // the other policies keep the first path found to each node
+        let report = graph.search(1, 2, FrontierPolicy::Fifo, 0);
+        assert_eq!(report.path, Some(vec![1, 2]));
+    }
+
+    #[test]
+    fn beam_search_does_not_report_a_goal_dropped_from_the_beam() {
+        let mut graph = GraphMap::<usize, f64, Undirected>::new();
+        graph.add_edge(1, 2, 1.0);
+        graph.add_edge(1, 3, 1.0);
+        let heuristic = HashMap::from([(1, 0.0), (2, 0.0), (3, 5.0)]);
+        let report = graph.beam_search(1, 3, &heuristic, 1);
+        assert_eq!(report.status, SearchStatus::Cutoff);
+        assert_eq!(report.path, None);
+        assert_eq!(report.expanded_nodes, vec![1, 2]);
+        assert!(!report.search_tree.contains_key(&3));
+
+        let report = graph.beam_search(1, 3, &heuristic, 2);
+        assert_eq!(report.status, SearchStatus::Found);
+        assert_eq!(report.path, Some(vec![1, 3]));
+
+        // nothing is dropped when the goal is not reachable at all
+        graph.add_node(4);
+        let report = graph.beam_search(1, 4, &heuristic, 2);
+        assert_eq!(report.status, SearchStatus::Failure);
+    }
+
+    #[test]
+    fn depth_limited_search_tells_cutoff_from_failure() {
+        let mut graph = directed(&[(1, 2, 1.0), (2, 3, 1.0), (3, 4, 1.0)]);
+        graph.add_node(5);
+
+        let report = graph.depth_limited_search(1, 4, 2);
+        assert_eq!(report.status, SearchStatus::Cutoff);
+        assert_eq!(report.path, None);
+
+        let report = graph.depth_limited_search(1, 4, 3);
+        assert_eq!(report.status, SearchStatus::Found);
+        assert_eq!(report.path, Some(vec![1, 2, 3, 4]));
+
+        let report = graph.depth_limited_search(1, 5, 10);
+        assert_eq!(report.status, SearchStatus::Failure);
+        assert_eq!(report.expanded_nodes, vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn iddfs_records_every_iteration() {
+        let mut graph = directed(&[(1, 2, 1.0), (2, 3, 1.0), (3, 4, 1.0)]);
+        graph.add_node(5);
+        let iteration = |limit, count, status| SearchIteration {
+            bound: SearchBound::Depth(limit),
+            generated: count,
+            expanded: count,
+            status,
+        };
+
+        let report = graph.iddfs(1, 4, 10);
+        assert_eq!(report.status, SearchStatus::Found);
+        assert_eq!(report.path, Some(vec![1, 2, 3, 4]));
+        assert_eq!(
+            report.iterations,
+            vec![
+                iteration(0, 1, SearchStatus::Cutoff),
+                iteration(1, 2, SearchStatus::Cutoff),
+                iteration(2, 3, SearchStatus::Cutoff),
+                iteration(3, 4, SearchStatus::Found),
+            ]
+        );
+        assert_eq!(report.expanded_nodes.len(), 1 + 2 + 3 + 4);
+
+        let report = graph.iddfs(1, 4, 2);
+        assert_eq!(report.status, SearchStatus::Cutoff);
+        assert_eq!(report.iterations.len(), 3);
+
+        // the search stops at the first limit that explores the whole reachable graph
+        let report = graph.iddfs(1, 5, 10);
+        assert_eq!(report.status, SearchStatus::Failure);
+        assert_eq!(report.iterations.len(), 5);
+        assert_eq!(report.iterations[4], iteration(4, 4, SearchStatus::Failure));
+    }
+
+    #[test]
+    fn bidirectional_bfs_follows_predecessors_backward() {
+        // the edge 5 -> 1 would join the searches at once if the backward search followed
+        // the successors of the goal
+        let graph = directed(&[
+            (1, 2, 1.0),
+            (2, 3, 1.0),
+            (3, 4, 1.0),
+            (4, 5, 1.0),
+            (1, 6, 5.0),
+            (6, 5, 5.0),
+            (5, 1, 1.0),
+        ]);
+        let report = graph.bidirectional_bfs(1, 5);
+        assert_eq!(report.status, SearchStatus::Found);
+        assert_eq!(report.path, Some(vec![1, 6, 5]));
+        assert_eq!(report.distance, Some(10.0));
+        let [forward, backward] = report.directions.unwrap();
+        assert_eq!(forward.generated, vec![1, 2, 6]);
+        assert_eq!(forward.expanded, vec![1]);
+        assert_eq!(backward.generated, vec![5, 4, 6]);
+        assert_eq!(backward.expanded, vec![5]);
+
+        let report = graph.bidirectional_bfs(5, 2);
+        assert_eq!(report.path, Some(vec![5, 1, 2]));
+    }
+
+    #[test]
+    fn ida_star_raises_the_bound_to_the_optimal_cost() {
+        let mut graph = GraphMap::<usize, f64, Undirected>::new();
+        graph.add_edge(1, 2, 2.0);
+        graph.add_edge(2, 4, 2.0);
+        graph.add_edge(1, 3, 1.0);
+        graph.add_edge(3, 4, 5.0);
+        let heuristic = HashMap::from([(1, 2.0), (2, 1.0), (3, 1.0), (4, 0.0)]);
+        let report = graph.ida_star(1, 4, &heuristic);
+        let bounds: Vec<_> = report
+            .iterations
+            .iter()
+            .map(|iteration| iteration.bound)
+            .collect();
+        assert_eq!(
+            bounds,
+            vec![
+                SearchBound::Cost(2.0),
+                SearchBound::Cost(3.0),
+                SearchBound::Cost(4.0)
+            ]
+        );
+        let astar = graph.astar(1, 4, &heuristic);
+        assert_eq!(report.status, SearchStatus::Found);
+        assert_eq!(report.path, astar.path);
+        assert_eq!(report.path, Some(vec![1, 2, 4]));
+        assert_eq!(report.distance, astar.distance);
+        assert_eq!(
+            report.iterations.last().map(|iteration| iteration.bound),
+            astar.distance.map(SearchBound::Cost)
+        );
+    }
+
+    fn directed(edges: &[(usize, usize, f64)]) -> GraphMap<usize, f64, Directed> {
+        let mut graph = GraphMap::new();
+        for &(from, to, weight) in edges {
+            graph.add_edge(from, to, weight);
+        }
+        graph
+    }
+
+    /// Asserts that `cycle` is a cycle of `graph` with a negative total weight.
+    fn assert_negative_cycle(graph: &GraphMap<usize, f64, Directed>, cycle: &[usize]) {
+        let mut closed = cycle.to_vec();
+        closed.push(cycle[0]);
+        let weight = graph
+            .path_weight(&closed)
+            .expect("the cycle is not in the graph");
+        assert!(weight < 0.0, "{cycle:?} has weight {weight}");
+    }
+
+    #[test]
+    fn bellman_ford_reports_a_reachable_negative_cycle() {
+        let graph = directed(&[(1, 2, 1.0), (2, 3, -2.0), (3, 2, 1.0), (3, 4, 1.0)]);
+        let NegativeCycle(cycle) = graph.bellman_ford(1).unwrap_err();
+        let mut nodes = cycle.clone();
+        nodes.sort();
+        assert_eq!(nodes, vec![2, 3]);
+        assert_negative_cycle(&graph, &cycle);
+    }
+
+    #[test]
+    fn bellman_ford_ignores_an_unreachable_negative_cycle() {
+        let graph = directed(&[(1, 2, 1.0), (2, 3, 2.0), (4, 5, -2.0), (5, 4, 1.0)]);
+        let tree = graph.bellman_ford(1).unwrap();
+        assert_eq!(
+            tree.distances,
+            HashMap::from([(1, 0.0), (2, 1.0), (3, 3.0)])
+        );
+        assert_eq!(tree.parents, HashMap::from([(2, 1), (3, 2)]));
+        assert!(graph.johnson().is_err());
+    }
+
+    #[test]
+    fn bellman_ford_reports_a_negative_self_loop() {
+        let graph = directed(&[(1, 2, 1.0), (2, 2, -1.0), (2, 3, 1.0)]);
+        assert_eq!(graph.bellman_ford(1).unwrap_err(), NegativeCycle(vec![2]));
+        assert_eq!(
+            graph.bellman_ford(3).unwrap().distances,
+            HashMap::from([(3, 0.0)])
+        );
+    }
+
+    #[test]
+    fn bellman_ford_reports_a_negative_cycle_through_the_start() {
+        let graph = directed(&[(1, 2, 1.0), (2, 1, -3.0)]);
+        let NegativeCycle(cycle) = graph.bellman_ford(1).unwrap_err();
+        let mut nodes = cycle.clone();
+        nodes.sort();
+        assert_eq!(nodes, vec![1, 2]);
+        assert_negative_cycle(&graph, &cycle);
+    }
+
+    /// Builds the graph of an input file of the `usig` binary, where `-1` means no edge.
+    fn parse_matrix(input: &str) -> GraphMap<usize, f64, Undirected> {
+        let mut tokens = input.split_whitespace();
+        let nodes: usize = tokens.next().unwrap().parse().unwrap();
+        let mut graph = GraphMap::new();
+        for i in 1..nodes {
+            for j in i + 1..=nodes {
+                let weight: f64 = tokens.next().unwrap().parse().unwrap();
+                if weight != -1.0 {
+                    graph.add_edge(i, j, weight);
+                }
+            }
+        }
+        graph
+    }
+
+    fn assert_close(expected: Option<f64>, actual: Option<f64>, pair: (usize, usize)) {
+        match (expected, actual) {
+            (Some(expected), Some(actual)) => {
+                assert!(
+                    (expected - actual).abs() < 1e-9,
+                    "{pair:?}: {expected} != {actual}"
+                )
+            }
+            _ => assert_eq!(expected, actual, "{pair:?}"),
+        }
+    }
+
+    #[test]
+    fn floyd_warshall_and_johnson_agree() {
+        let graph = parse_matrix(include_str!("../Grafo1.txt"));
+        let floyd = graph.floyd_warshall().unwrap();
+        let johnson = graph.johnson().unwrap();
+        for from in graph.nodes() {
+            for to in graph.nodes() {
+                let expected = floyd.distance(from, to);
+                let actual = johnson.distance(from, to);
+                assert_close(expected, actual, (from, to));
+                for matrix in [&floyd, &johnson] {
+                    let path = matrix.path(from, to);
+                    let weight = path.and_then(|path| graph.path_weight(&path));
+                    assert_close(expected, weight, (from, to));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn undirected_negative_edge_is_a_negative_cycle() {
+        let mut graph = GraphMap::<usize, f64, Undirected>::new();
+        graph.add_edge(1, 2, 1.0);
+        graph.add_edge(2, 3, -1.0);
+        graph.add_edge(3, 4, 1.0);
+        for result in [graph.floyd_warshall(), graph.johnson()] {
+            let NegativeCycle(mut cycle) = result.unwrap_err();
+            cycle.sort();
+            assert_eq!(cycle, vec![2, 3]);
+        }
+    }
+
+    #[test]
+    fn k_shortest_paths_are_ranked_by_distance() {
+        let mut graph = GraphMap::<usize, f64, Undirected>::new();
+        graph.add_edge(1, 2, 1.0);
+        graph.add_edge(2, 4, 1.0);
+        graph.add_edge(1, 3, 1.0);
+        graph.add_edge(3, 4, 1.0);
+        graph.add_edge(1, 4, 3.0);
+        graph.add_edge(4, 5, 1.0);
+
+        let paths = graph.k_shortest_paths(1, 4, 10);
+        let distances: Vec<_> = paths.iter().map(|&(_, distance)| distance).collect();
+        assert_eq!(distances, vec![2.0, 2.0, 3.0]);
+        let mut tied: Vec<_> = paths[..2].iter().map(|(path, _)| path.clone()).collect();
+        tied.sort();
+        assert_eq!(tied, vec![vec![1, 2, 4], vec![1, 3, 4]]);
+        assert_eq!(paths[2].0, vec![1, 4]);
+
+        assert_eq!(graph.k_shortest_paths(1, 4, 2), paths[..2]);
+        assert_eq!(graph.k_shortest_paths(1, 4, 0), vec![]);
+        assert_eq!(graph.k_shortest_paths(1, 1, 3), vec![(vec![1], 0.0)]);
+        assert_eq!(graph.k_shortest_paths(1, 6, 3), vec![]);
+    }
+
+    #[test]
+    fn all_simple_paths_yield_their_distance() {
+        let mut graph = GraphMap::<usize, f64, Undirected>::new();
+        graph.add_edge(1, 2, 1.0);
+        graph.add_edge(2, 3, 2.0);
+        graph.add_edge(1, 3, 5.0);
+        graph.add_edge(3, 3, 1.0);
+        let mut paths: Vec<_> = graph.all_simple_paths(1, 3, 0, None).collect();
+        paths.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+        assert_eq!(paths, vec![(vec![1, 2, 3], 3.0), (vec![1, 3], 5.0)]);
+        for (path, distance) in &paths {
+            assert_eq!(graph.path_weight(path), Some(*distance));
+        }
+        assert_eq!(
+            graph.all_simple_paths(1, 1, 0, None).collect::<Vec<_>>(),
+            vec![(vec![1], 0.0)]
+        );
+    }
+
+    #[test]
+    fn pruning_the_goal_on_expand_still_reaches_it() {
+        let mut graph = GraphMap::<usize, f64, Undirected>::new();
+        graph.add_edge(1, 2, 1.0);
+        graph.add_edge(2, 3, 1.0);
+        let report = graph.bfs_with(1, 2, |event| match event {
+            SearchEvent::Expand(_) => Control::Prune,
+            _ => Control::Continue,
+        });
+        assert_eq!(report.status, SearchStatus::Cutoff);
+        assert_eq!(report.expanded_nodes, vec![1]);
+
+        let report = graph.bfs_with(1, 2, |event| match event {
+            SearchEvent::Expand(2) => Control::Prune,
+            _ => Control::Continue,
+        });
+        assert_eq!(report.status, SearchStatus::Found);
+        assert_eq!(report.path, Some(vec![1, 2]));
+        assert_eq!(report.expanded_nodes, vec![1, 2]);
+    }
+}
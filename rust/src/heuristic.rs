@@ -0,0 +1,33 @@
+//! Heuristics used by the informed search algorithms.
+
+use std::{collections::HashMap, hash::Hash};
+
+/// An estimate of the cost of reaching the goal from a node.
+///
+/// Closures `Fn(TNode) -> TEdge` are heuristics, and so are tables of estimates stored in a
+/// `HashMap`, where nodes missing from the table are estimated as `TEdge::default()`.
+pub trait Heuristic<TNode, TEdge> {
+    /// Returns the estimated cost of reaching the goal from `node`.
+    fn estimate(&self, node: TNode) -> TEdge;
+}
+
+impl<TNode, TEdge, F> Heuristic<TNode, TEdge> for F
+where
+    F: Fn(TNode) -> TEdge,
+{
+    #[inline]
+    fn estimate(&self, node: TNode) -> TEdge {
+        self(node)
+    }
+}
+
+impl<TNode, TEdge> Heuristic<TNode, TEdge> for HashMap<TNode, TEdge>
+where
+    TNode: Eq + Hash,
+    TEdge: Default + Copy,
+{
+    #[inline]
+    fn estimate(&self, node: TNode) -> TEdge {
+        self.get(&node).copied().unwrap_or_default()
+    }
+}
@@ -0,0 +1,115 @@
+//! Iterative deepening A* over implicit graphs.
+
+use std::hash::Hash;
+
+use crate::{GraphSearchReport, Heuristic, SearchBound, SearchIteration, SearchStatus};
+
+/// Performs an iterative deepening A* search (IDA*) over an implicit graph, starting from
+/// the given start node and searching for a node that satisfies `is_goal`.
+///
+/// The graph is never materialized: `successors` returns the neighbors of a node together
+/// with the weight of the edge that reaches them. Each iteration is a depth-first search
+/// that prunes the nodes whose `g + h` exceeds the current threshold, and only avoids the
+/// nodes of the current path, so memory is proportional to the depth of the search. The
+/// next threshold is the lowest `g + h` pruned by the previous iteration.
+///
+/// The threshold and the generated and expanded counts of every iteration are recorded in
+/// [`GraphSearchReport::iterations`]. If no goal is reachable the search only ends once
+/// the whole graph fits under the threshold, so it may never end on an infinite graph.
+pub fn ida_star<TNode, TEdge, FS, IS, FG, H>(
+    start: TNode,
+    mut successors: FS,
+    mut is_goal: FG,
+    heuristic: &H,
+) -> GraphSearchReport<TNode, TEdge>
+where
+    TNode: Copy + Eq + Hash,
+    TEdge: Default + Copy + PartialOrd + std::ops::Add<Output = TEdge>,
+    FS: FnMut(TNode) -> IS,
+    IS: IntoIterator<Item = (TNode, TEdge)>,
+    FG: FnMut(TNode) -> bool,
+    H: Heuristic<TNode, TEdge> + ?Sized,
+{
+    let mut generated = Vec::new();
+    let mut expanded = Vec::new();
+    let mut iterations = Vec::new();
+    let mut threshold = heuristic.estimate(start);
+    loop {
+        let mut iteration_generated = vec![start];
+        let mut iteration_expanded = Vec::new();
+        let mut path = vec![(start, TEdge::default(), TEdge::default())];
+        let mut stack = Vec::new();
+        let mut next_threshold: Option<TEdge> = None;
+        let mut found = false;
+        iteration_expanded.push(start);
+        if is_goal(start) {
+            found = true;
+        } else {
+            stack.push(successors(start).into_iter());
+        }
+        while let Some(neighbors) = stack.last_mut() {
+            let Some((node, weight)) = neighbors.next() else {
+                stack.pop();
+                path.pop();
+                continue;
+            };
+            iteration_generated.push(node);
+            if path.iter().any(|&(other, _, _)| other == node) {
+                continue;
+            }
+            let cost = path[path.len() - 1].1 + weight;
+            let estimate = cost + heuristic.estimate(node);
+            if estimate > threshold {
+                if next_threshold.is_none_or(|next| estimate < next) {
+                    next_threshold = Some(estimate);
+                }
+                continue;
+            }
+            iteration_expanded.push(node);
+            path.push((node, cost, weight));
+            if is_goal(node) {
+                found = true;
+                break;
+            }
+            stack.push(successors(node).into_iter());
+        }
+        let status = match (found, next_threshold) {
+            (true, _) => SearchStatus::Found,
+            (false, Some(_)) => SearchStatus::Cutoff,
+            (false, None) => SearchStatus::Failure,
+        };
+        iterations.push(SearchIteration {
+            bound: SearchBound::Cost(threshold),
+            generated: iteration_generated.len(),
+            expanded: iteration_expanded.len(),
+            status,
+        });
+        generated.append(&mut iteration_generated);
+        expanded.append(&mut iteration_expanded);
+        match (status, next_threshold) {
+            (SearchStatus::Cutoff, Some(next)) => threshold = next,
+            (SearchStatus::Found, _) => {
+                return GraphSearchReport {
+                    path: Some(path.iter().map(|&(node, _, _)| node).collect()),
+                    path_weights: path[1..].iter().map(|&(_, _, weight)| weight).collect(),
+                    distance: Some(path[path.len() - 1].1),
+                    search_tree: path.windows(2).map(|pair| (pair[1].0, pair[0].0)).collect(),
+                    generated_nodes: generated,
+                    expanded_nodes: expanded,
+                    status,
+                    iterations,
+                    ..Default::default()
+                };
+            }
+            _ => {
+                return GraphSearchReport {
+                    generated_nodes: generated,
+                    expanded_nodes: expanded,
+                    status,
+                    iterations,
+                    ..Default::default()
+                };
+            }
+        }
+    }
+}
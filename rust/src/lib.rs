@@ -1,10 +1,29 @@
+pub mod event;
+pub mod frontier;
+pub mod goal;
 pub mod graphmap;
+pub mod heuristic;
+pub mod idastar;
+pub mod problem;
+pub mod scored;
+pub mod step;
+pub mod visit;
 
 #[macro_use]
 pub mod macros;
 
+pub use crate::event::*;
+pub use crate::frontier::*;
+pub use crate::goal::*;
 pub use crate::graphmap::*;
+// every macro is `#[macro_export]`ed, so this glob is empty until the module has items
+#[allow(unused_imports)]
 pub use crate::macros::*;
+pub use crate::heuristic::*;
+pub use crate::idastar::*;
+pub use crate::problem::*;
+pub use crate::step::*;
+pub use crate::visit::*;
 
 pub use crate::Direction::{Incoming, Outgoing};
 
@@ -0,0 +1,240 @@
+//! Search over implicit state spaces.
+
+use std::{
+    collections::{BinaryHeap, HashMap, HashSet},
+    ops::Add,
+};
+
+use crate::{
+    scored::MinScored, EdgeWeightLookup, FrontierPolicy, FrontierSearch, Goal, GoalMode, GraphBase,
+    GraphSearchReport, IntoNeighbors, NodeTrait,
+};
+
+/// A search problem over a state space that is explored on demand instead of being built
+/// as a graph up front, such as the 8-puzzle or the water jugs.
+pub trait SearchProblem {
+    /// The states of the problem, which are the nodes of the search.
+    type State: NodeTrait;
+    /// The cost of a step between two states.
+    type Cost: Default + Copy + PartialOrd + Add<Output = Self::Cost>;
+
+    /// Returns the state the search starts from.
+    fn initial_state(&self) -> Self::State;
+
+    /// Returns the states reachable from `state` in one step, together with the cost of
+    /// each step.
+    fn successors(&self, state: Self::State) -> Vec<(Self::State, Self::Cost)>;
+
+    /// Returns `true` if `state` solves the problem.
+    fn is_goal(&self, state: Self::State) -> bool;
+}
+
+impl<P> SearchProblem for &P
+where
+    P: SearchProblem + ?Sized,
+{
+    type State = P::State;
+    type Cost = P::Cost;
+
+    #[inline]
+    fn initial_state(&self) -> P::State {
+        (**self).initial_state()
+    }
+
+    #[inline]
+    fn successors(&self, state: P::State) -> Vec<(P::State, P::Cost)> {
+        (**self).successors(state)
+    }
+
+    #[inline]
+    fn is_goal(&self, state: P::State) -> bool {
+        (**self).is_goal(state)
+    }
+}
+
+/// The problem of finding a path in a graph from a start node to a node that satisfies a
+/// goal test, where the states are the nodes and the steps are the edges of the graph.
+pub struct GraphProblem<G, T>
+where
+    G: GraphBase,
+{
+    graph: G,
+    start: G::NodeId,
+    goal: T,
+}
+
+impl<G, T> GraphProblem<G, T>
+where
+    G: GraphBase,
+{
+    /// Creates the problem of reaching a node that satisfies `goal` from `start` in
+    /// `graph`.
+    pub fn new(graph: G, start: G::NodeId, goal: T) -> Self {
+        Self { graph, start, goal }
+    }
+}
+
+impl<G, T> SearchProblem for GraphProblem<G, T>
+where
+    G: IntoNeighbors + EdgeWeightLookup,
+    G::NodeId: NodeTrait,
+    G::EdgeWeight: Default + Copy + PartialOrd + Add<Output = G::EdgeWeight>,
+    T: Goal<G::NodeId>,
+{
+    type State = G::NodeId;
+    type Cost = G::EdgeWeight;
+
+    fn initial_state(&self) -> G::NodeId {
+        self.start
+    }
+
+    fn successors(&self, state: G::NodeId) -> Vec<(G::NodeId, G::EdgeWeight)> {
+        self.graph
+            .neighbors(state)
+            .filter_map(|neighbor| Some((neighbor, self.graph.edge_weight(state, neighbor)?)))
+            .collect()
+    }
+
+    fn is_goal(&self, state: G::NodeId) -> bool {
+        self.goal.is_goal(state)
+    }
+}
+
+/// Performs a breadth-first search on the state space of `problem`.
+///
+/// See [`graph_search`] for the details.
+pub fn breadth_first_search<P>(problem: &P) -> GraphSearchReport<P::State, P::Cost>
+where
+    P: SearchProblem + ?Sized,
+{
+    graph_search(problem, FrontierPolicy::Fifo, 0)
+}
+
+/// Performs a depth-first search on the state space of `problem`.
+///
+/// See [`graph_search`] for the details.
+pub fn depth_first_search<P>(problem: &P) -> GraphSearchReport<P::State, P::Cost>
+where
+    P: SearchProblem + ?Sized,
+{
+    graph_search(problem, FrontierPolicy::Lifo, 0)
+}
+
+/// Performs a graph search on the state space of `problem`, where the next state to expand
+/// is chosen by `policy`.
+///
+/// This runs a [`FrontierSearch`] to the end. Only the states reached are ever stored, so
+/// the search ends on an infinite state space only if a goal is found.
+/// [`GraphMap::search`](crate::GraphMap::search) runs this search on a graph.
+///
+/// # Arguments
+///
+/// * `problem` - The problem to solve.
+/// * `policy` - How the next state to expand is chosen from the frontier.
+/// * `seed` - The seed of the random number generator used by random policies.
+pub fn graph_search<P>(
+    problem: &P,
+    policy: FrontierPolicy,
+    seed: u64,
+) -> GraphSearchReport<P::State, P::Cost>
+where
+    P: SearchProblem + ?Sized,
+{
+    FrontierSearch::new(problem, policy, seed).run()
+}
+
+/// Performs a uniform-cost search on the state space of `problem`.
+///
+/// States are expanded in order of increasing path cost, so the solution found is the one
+/// with the lowest cost as long as no step has a negative cost.
+/// [`GraphMap::ucs`](crate::GraphMap::ucs) runs this search on a graph.
+pub fn uniform_cost_search<P>(problem: &P) -> GraphSearchReport<P::State, P::Cost>
+where
+    P: SearchProblem + ?Sized,
+{
+    let start = problem.initial_state();
+    multi_source_uniform_cost_search(problem, [(start, P::Cost::default())], GoalMode::First)
+}
+
+/// Performs a uniform-cost search that starts from several sources at once, each one
+/// reached with its own initial cost, instead of the initial state of `problem`.
+///
+/// Every source is pushed to the queue with its initial cost, and a source repeated keeps
+/// its lowest cost. When there is more than one distinct source, the source each reached
+/// state originates from is recorded in [`GraphSearchReport::origins`]. It is updated
+/// along with the parent whenever a cheaper path is found, so every state is assigned to
+/// the source it is closest to, counting the initial costs.
+/// [`GraphMap::multi_source_ucs`](crate::GraphMap::multi_source_ucs) runs this search on a
+/// graph.
+///
+/// # Arguments
+///
+/// * `problem` - The problem to solve.
+/// * `sources` - The states the search starts from, with their initial costs.
+/// * `mode` - Whether to stop at the first goal or to reach all of them.
+pub fn multi_source_uniform_cost_search<P, I>(
+    problem: &P,
+    sources: I,
+    mode: GoalMode,
+) -> GraphSearchReport<P::State, P::Cost>
+where
+    P: SearchProblem + ?Sized,
+    I: IntoIterator<Item = (P::State, P::Cost)>,
+{
+    let mut parents = HashMap::new();
+    let mut costs = HashMap::new();
+    let mut origins = HashMap::new();
+    let mut closed = HashSet::new();
+    let mut queue = BinaryHeap::new();
+    let mut generated = Vec::new();
+    let mut expanded = Vec::new();
+    let mut goals = Vec::new();
+    let mut distance = None;
+    for (source, cost) in sources {
+        generated.push(source);
+        if costs.get(&source).is_none_or(|&old| cost < old) {
+            costs.insert(source, cost);
+            origins.insert(source, source);
+            queue.push(MinScored(cost, source));
+        }
+    }
+    let distinct = origins.len();
+    while let Some(MinScored(cost, state)) = queue.pop() {
+        if !closed.insert(state) {
+            // a cheaper path to this state was already expanded
+            continue;
+        }
+        expanded.push(state);
+        if problem.is_goal(state) {
+            goals.push(state);
+            distance.get_or_insert(cost);
+            if mode == GoalMode::First {
+                break;
+            }
+        }
+        for (successor, step) in problem.successors(state) {
+            generated.push(successor);
+            if closed.contains(&successor) {
+                continue;
+            }
+            let next_cost = cost + step;
+            if costs.get(&successor).is_none_or(|&old| next_cost < old) {
+                costs.insert(successor, next_cost);
+                parents.insert(successor, (state, step));
+                let origin = origins[&state];
+                origins.insert(successor, origin);
+                queue.push(MinScored(next_cost, successor));
+            }
+        }
+    }
+    let first = goals.first().copied().zip(distance);
+    GraphSearchReport {
+        goals,
+        origins: if distinct > 1 {
+            origins
+        } else {
+            HashMap::new()
+        },
+        ..GraphSearchReport::from_tree(parents, first, generated, expanded)
+    }
+}
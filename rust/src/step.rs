@@ -0,0 +1,392 @@
+//! Searches that advance one expansion at a time.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    Control, Frontier, FrontierPolicy, GoalMode, GraphSearchReport, SearchEvent, SearchProblem,
+    SearchSnapshot, SearchStatus, VisitMap,
+};
+
+/// A graph search on the state space of a [`SearchProblem`] that is advanced by hand, one
+/// expansion per call to [`FrontierSearch::step`], so that its state can be inspected in
+/// between.
+///
+/// Nodes are marked as seen when generated, so every node enters the frontier at most
+/// once, and the goal test is applied when a node is expanded. With
+/// [`FrontierPolicy::LowestCost`] the cost and parent of a node still in the frontier are
+/// updated when a cheaper path to it is found, which makes it a uniform-cost search as long
+/// as no step has a negative cost. This is the search behind
+/// [`graph_search`](crate::graph_search), [`frontier_search`](crate::frontier_search) and
+/// the searches of [`GraphMap`](crate::GraphMap) built on them.
+pub struct FrontierSearch<P, M = HashSet<<P as SearchProblem>::State>>
+where
+    P: SearchProblem,
+{
+    problem: P,
+    policy: FrontierPolicy,
+    seed: u64,
+    mode: GoalMode,
+    /// The sources not yet added to the frontier, which happens in the first step.
+    sources: Option<Vec<(P::State, P::Cost)>>,
+    frontier: Frontier<P::State, P::Cost>,
+    seen: M,
+    parents: HashMap<P::State, (P::State, P::Cost)>,
+    origins: Option<HashMap<P::State, P::State>>,
+    generated: Vec<P::State>,
+    expanded: Vec<P::State>,
+    goals: Vec<P::State>,
+    distance: Option<P::Cost>,
+    cutoff: bool,
+    finished: bool,
+    trace: Option<Vec<SearchSnapshot<P::State, P::Cost>>>,
+}
+
+impl<P> FrontierSearch<P>
+where
+    P: SearchProblem,
+{
+    /// Creates a search on the state space of `problem` from its initial state, where the
+    /// next node to expand is chosen by `policy`. Nothing is expanded until the first step.
+    ///
+    /// # Arguments
+    ///
+    /// * `problem` - The problem to solve.
+    /// * `policy` - How the next node to expand is chosen from the frontier.
+    /// * `seed` - The seed of the random number generator used by random policies.
+    pub fn new(problem: P, policy: FrontierPolicy, seed: u64) -> Self {
+        let start = problem.initial_state();
+        Self::from_sources(problem, [(start, P::Cost::default())], policy, seed)
+    }
+
+    /// Creates a search that starts from several sources at once, each one reached with its
+    /// own initial cost, instead of the initial state of `problem`.
+    ///
+    /// The frontier is seeded with every source in the given order, and a source repeated
+    /// keeps its first cost. When there is more than one distinct source, the source each
+    /// reached node originates from is recorded in [`GraphSearchReport::origins`], and with
+    /// [`FrontierPolicy::LowestCost`] it changes along with the parent of a node when a
+    /// cheaper path to it is found.
+    pub fn from_sources<I>(problem: P, sources: I, policy: FrontierPolicy, seed: u64) -> Self
+    where
+        I: IntoIterator<Item = (P::State, P::Cost)>,
+    {
+        let sources: Vec<_> = sources.into_iter().collect();
+        let distinct: HashSet<_> = sources.iter().map(|&(source, _)| source).collect();
+        Self {
+            problem,
+            policy,
+            seed,
+            mode: GoalMode::First,
+            sources: Some(sources),
+            frontier: Frontier::new(policy, seed),
+            seen: HashSet::new(),
+            parents: HashMap::new(),
+            origins: (distinct.len() > 1).then(HashMap::new),
+            generated: Vec::new(),
+            expanded: Vec::new(),
+            goals: Vec::new(),
+            distance: None,
+            cutoff: false,
+            finished: false,
+            trace: None,
+        }
+    }
+
+    /// Creates a breadth-first search on the state space of `problem`.
+    pub fn bfs(problem: P) -> Self {
+        Self::new(problem, FrontierPolicy::Fifo, 0)
+    }
+
+    /// Creates a depth-first search on the state space of `problem`.
+    pub fn dfs(problem: P) -> Self {
+        Self::new(problem, FrontierPolicy::Lifo, 0)
+    }
+}
+
+impl<P, M> FrontierSearch<P, M>
+where
+    P: SearchProblem,
+    M: VisitMap<P::State>,
+{
+    /// Keeps track of the seen nodes in `map` instead of a [`HashSet`], such as the one
+    /// created by [`Visitable::visit_map`](crate::Visitable::visit_map). The map must be
+    /// empty.
+    pub fn with_visit_map<N>(self, map: N) -> FrontierSearch<P, N>
+    where
+        N: VisitMap<P::State>,
+    {
+        FrontierSearch {
+            problem: self.problem,
+            policy: self.policy,
+            seed: self.seed,
+            mode: self.mode,
+            sources: self.sources,
+            frontier: self.frontier,
+            seen: map,
+            parents: self.parents,
+            origins: self.origins,
+            generated: self.generated,
+            expanded: self.expanded,
+            goals: self.goals,
+            distance: self.distance,
+            cutoff: self.cutoff,
+            finished: self.finished,
+            trace: self.trace,
+        }
+    }
+
+    /// Sets whether the search stops at the first goal, the default, or goes on until the
+    /// frontier is empty. The goals reached are recorded in [`GraphSearchReport::goals`],
+    /// and the path and distance of the report are the ones of the first goal.
+    pub fn with_goal_mode(mut self, mode: GoalMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Turns on trace mode, so that the state of the search after every step is recorded in
+    /// [`GraphSearchReport::trace`].
+    pub fn with_trace(mut self) -> Self {
+        self.trace.get_or_insert_with(Vec::new);
+        self
+    }
+
+    /// Expands the next node of the frontier, returning it, or returns `None` if the search
+    /// has already finished.
+    ///
+    /// The search finishes in the step that expands the goal, or in the first step that
+    /// finds the frontier empty.
+    pub fn step(&mut self) -> Option<P::State> {
+        self.step_with(&mut |_| Control::Continue)
+    }
+
+    /// Performs the same step as [`FrontierSearch::step`], calling `visitor` on every
+    /// event of the step.
+    ///
+    /// The [`Control`] returned by the visitor can prune part of the search or stop it. If
+    /// no goal is found after the visitor pruned or stopped the search, the status of the
+    /// search is [`SearchStatus::Cutoff`], since a goal may still be reachable.
+    pub fn step_with<V>(&mut self, visitor: &mut V) -> Option<P::State>
+    where
+        V: FnMut(SearchEvent<P::State, P::Cost>) -> Control,
+    {
+        if self.finished {
+            return None;
+        }
+        if let Some(sources) = self.sources.take() {
+            if self.seed_sources(sources, visitor) == Control::Break {
+                self.stop();
+                return None;
+            }
+        }
+        let Some((node, cost)) = self.frontier.pop() else {
+            self.finished = true;
+            return None;
+        };
+        self.expanded.push(node);
+        let control = self.expand(node, cost, visitor);
+        if control == Control::Break {
+            self.stop();
+        }
+        self.record(node);
+        Some(node)
+    }
+
+    /// Adds the sources to the frontier, returning [`Control::Break`] if the visitor
+    /// stopped the search.
+    fn seed_sources<V>(&mut self, sources: Vec<(P::State, P::Cost)>, visitor: &mut V) -> Control
+    where
+        V: FnMut(SearchEvent<P::State, P::Cost>) -> Control,
+    {
+        for (source, cost) in sources {
+            self.generated.push(source);
+            if !self.seen.visit(source) {
+                if visitor(SearchEvent::Prune(source)) == Control::Break {
+                    return Control::Break;
+                }
+                continue;
+            }
+            if let Some(origins) = &mut self.origins {
+                origins.insert(source, source);
+            }
+            match visitor(SearchEvent::Discover(source, cost)) {
+                Control::Continue => self.frontier.push(source, cost),
+                Control::Prune => self.cutoff = true,
+                Control::Break => return Control::Break,
+            }
+        }
+        Control::Continue
+    }
+
+    /// Expands `node`, reached with the path cost `cost`, returning [`Control::Break`] if
+    /// the search stops after it.
+    fn expand<V>(&mut self, node: P::State, cost: P::Cost, visitor: &mut V) -> Control
+    where
+        V: FnMut(SearchEvent<P::State, P::Cost>) -> Control,
+    {
+        let control = visitor(SearchEvent::Expand(node));
+        if control == Control::Break {
+            return Control::Break;
+        }
+        // a pruned node is still tested, only its successors are skipped
+        if self.problem.is_goal(node) {
+            self.goals.push(node);
+            self.distance.get_or_insert(cost);
+            let control = visitor(SearchEvent::GoalReached(node));
+            if self.mode == GoalMode::First || control == Control::Break {
+                self.finished = true;
+                return Control::Continue;
+            }
+        }
+        if control == Control::Prune {
+            self.cutoff = true;
+            return Control::Continue;
+        }
+        for (successor, step) in self.problem.successors(node) {
+            self.generated.push(successor);
+            match visitor(SearchEvent::Generate(node, successor)) {
+                Control::Continue => {}
+                Control::Prune => {
+                    self.cutoff = true;
+                    continue;
+                }
+                Control::Break => return Control::Break,
+            }
+            let next_cost = cost + step;
+            // a node seen before is reached again only by a cheaper path while it is still
+            // in a lowest-cost frontier
+            let seen_before = !self.seen.visit(successor);
+            if seen_before && !self.lowers_cost(successor, next_cost) {
+                if visitor(SearchEvent::Prune(successor)) == Control::Break {
+                    return Control::Break;
+                }
+                continue;
+            }
+            match visitor(SearchEvent::Discover(successor, next_cost)) {
+                Control::Continue => {
+                    if let Some(origins) = &mut self.origins {
+                        let origin = origins[&node];
+                        origins.insert(successor, origin);
+                    }
+                    self.parents.insert(successor, (node, step));
+                    if seen_before {
+                        self.frontier.decrease_cost(successor, next_cost);
+                    } else {
+                        self.frontier.push(successor, next_cost);
+                    }
+                }
+                Control::Prune => self.cutoff = true,
+                Control::Break => return Control::Break,
+            }
+        }
+        visitor(SearchEvent::Finish(node))
+    }
+
+    /// Returns `true` if `node` is in the frontier with a higher cost than `cost` and the
+    /// policy orders the frontier by cost.
+    fn lowers_cost(&self, node: P::State, cost: P::Cost) -> bool {
+        self.policy == FrontierPolicy::LowestCost
+            && self
+                .frontier
+                .iter()
+                .any(|&(other, old)| other == node && cost < old)
+    }
+
+    /// Finishes the search because the visitor stopped it.
+    fn stop(&mut self) {
+        self.cutoff = true;
+        self.finished = true;
+    }
+
+    /// Records the state of the search after expanding `node`, if trace mode is on.
+    fn record(&mut self, node: P::State) {
+        if let Some(trace) = &mut self.trace {
+            trace.push(SearchSnapshot {
+                iteration: self.expanded.len(),
+                expanded: node,
+                frontier: self.frontier.iter().copied().collect(),
+                generated: self.generated.len(),
+            });
+        }
+    }
+
+    /// Runs the search until it finishes and returns its report.
+    pub fn run(self) -> GraphSearchReport<P::State, P::Cost> {
+        self.run_with(|_| Control::Continue)
+    }
+
+    /// Runs the search until it finishes, calling `visitor` on every event, and returns
+    /// its report.
+    ///
+    /// See [`FrontierSearch::step_with`] for how the visitor controls the search.
+    pub fn run_with<V>(mut self, mut visitor: V) -> GraphSearchReport<P::State, P::Cost>
+    where
+        V: FnMut(SearchEvent<P::State, P::Cost>) -> Control,
+    {
+        while self.step_with(&mut visitor).is_some() {}
+        self.report()
+    }
+
+    /// Returns how the search ended, or `None` if it has not finished yet.
+    pub fn status(&self) -> Option<SearchStatus> {
+        if !self.finished {
+            None
+        } else if !self.goals.is_empty() {
+            Some(SearchStatus::Found)
+        } else if self.cutoff {
+            Some(SearchStatus::Cutoff)
+        } else {
+            Some(SearchStatus::Failure)
+        }
+    }
+
+    /// Returns an iterator over the nodes of the frontier and their path costs, oldest
+    /// first.
+    pub fn frontier(&self) -> impl Iterator<Item = &(P::State, P::Cost)> {
+        self.frontier.iter()
+    }
+
+    /// Returns the nodes expanded so far, in order. No node is expanded twice, so these are
+    /// also the closed set of the search.
+    pub fn closed(&self) -> &[P::State] {
+        &self.expanded
+    }
+
+    /// Returns the nodes generated so far, in order, including the repeated ones.
+    pub fn generated(&self) -> &[P::State] {
+        &self.generated
+    }
+
+    /// Returns the node expanded by the last step, if any.
+    pub fn last_expanded(&self) -> Option<P::State> {
+        self.expanded.last().copied()
+    }
+
+    /// Returns the parent of every node reached so far, with the cost of the step from it.
+    pub fn search_tree(&self) -> &HashMap<P::State, (P::State, P::Cost)> {
+        &self.parents
+    }
+
+    /// Returns the report of the search as it stands. Until a goal is expanded, the report
+    /// has no path and its status is [`SearchStatus::Failure`], or
+    /// [`SearchStatus::Cutoff`] if the visitor pruned part of the search.
+    pub fn report(&self) -> GraphSearchReport<P::State, P::Cost> {
+        let first = self.goals.first().copied().zip(self.distance);
+        let report = GraphSearchReport::from_tree(
+            self.parents.clone(),
+            first,
+            self.generated.clone(),
+            self.expanded.clone(),
+        );
+        GraphSearchReport {
+            seed: self.policy.is_random().then_some(self.seed),
+            status: match report.status {
+                SearchStatus::Failure if self.cutoff => SearchStatus::Cutoff,
+                status => status,
+            },
+            goals: self.goals.clone(),
+            origins: self.origins.clone().unwrap_or_default(),
+            trace: self.trace.clone().unwrap_or_default(),
+            ..report
+        }
+    }
+}
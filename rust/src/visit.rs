@@ -0,0 +1,298 @@
+//! Graph traits and the searches written over them.
+//!
+//! Based of petgraph implementation.
+//!
+//! See https://github.com/petgraph/petgraph/blob/master/src/visit/mod.rs
+
+use std::{
+    collections::HashSet,
+    hash::{BuildHasher, Hash},
+    ops::Add,
+};
+
+use crate::{
+    Control, EdgeType, FrontierPolicy, FrontierSearch, GraphMap, GraphProblem, GraphSearchReport,
+    Neighbors, NodeTrait, SearchEvent,
+};
+
+/// The base of every graph trait, defining the types of the nodes and edge weights.
+pub trait GraphBase {
+    /// The identifier of a node of the graph.
+    type NodeId: Copy;
+    /// The weight of an edge of the graph.
+    type EdgeWeight;
+}
+
+/// A reference to a graph that can produce the neighbors of a node.
+pub trait IntoNeighbors: GraphBase + Copy {
+    type Neighbors: Iterator<Item = Self::NodeId>;
+
+    /// Returns the neighbors of `node`, which are its successors in directed graphs.
+    fn neighbors(self, node: Self::NodeId) -> Self::Neighbors;
+}
+
+/// A graph that knows how many nodes it has.
+pub trait NodeCount: GraphBase {
+    fn node_count(&self) -> usize;
+}
+
+/// A graph that can look up the weight of the edge between two nodes.
+pub trait EdgeWeightLookup: GraphBase {
+    /// Returns the weight of the edge from `a` to `b`, or `None` if there is no such edge.
+    fn edge_weight(&self, a: Self::NodeId, b: Self::NodeId) -> Option<Self::EdgeWeight>;
+}
+
+/// A set of visited nodes.
+pub trait VisitMap<TNode> {
+    /// Marks `node` as visited, returning `true` if it was not visited before.
+    fn visit(&mut self, node: TNode) -> bool;
+
+    /// Returns `true` if `node` has been visited.
+    fn is_visited(&self, node: &TNode) -> bool;
+}
+
+impl<TNode, S> VisitMap<TNode> for HashSet<TNode, S>
+where
+    TNode: Eq + Hash,
+    S: BuildHasher,
+{
+    #[inline]
+    fn visit(&mut self, node: TNode) -> bool {
+        self.insert(node)
+    }
+
+    #[inline]
+    fn is_visited(&self, node: &TNode) -> bool {
+        self.contains(node)
+    }
+}
+
+/// A graph that can create the map used to keep track of the visited nodes of a search.
+pub trait Visitable: GraphBase {
+    type Map: VisitMap<Self::NodeId>;
+
+    /// Creates an empty visit map.
+    fn visit_map(&self) -> Self::Map;
+
+    /// Clears a visit map so that it can be reused.
+    fn reset_map(&self, map: &mut Self::Map);
+}
+
+impl<G: GraphBase> GraphBase for &G {
+    type NodeId = G::NodeId;
+    type EdgeWeight = G::EdgeWeight;
+}
+
+impl<G: NodeCount> NodeCount for &G {
+    #[inline]
+    fn node_count(&self) -> usize {
+        (**self).node_count()
+    }
+}
+
+impl<G: EdgeWeightLookup> EdgeWeightLookup for &G {
+    #[inline]
+    fn edge_weight(&self, a: Self::NodeId, b: Self::NodeId) -> Option<Self::EdgeWeight> {
+        (**self).edge_weight(a, b)
+    }
+}
+
+impl<G: Visitable> Visitable for &G {
+    type Map = G::Map;
+
+    #[inline]
+    fn visit_map(&self) -> Self::Map {
+        (**self).visit_map()
+    }
+
+    #[inline]
+    fn reset_map(&self, map: &mut Self::Map) {
+        (**self).reset_map(map)
+    }
+}
+
+impl<TNode, TEdge, Ty> GraphBase for GraphMap<TNode, TEdge, Ty>
+where
+    TNode: NodeTrait,
+{
+    type NodeId = TNode;
+    type EdgeWeight = TEdge;
+}
+
+impl<'a, TNode, TEdge, Ty> IntoNeighbors for &'a GraphMap<TNode, TEdge, Ty>
+where
+    TNode: NodeTrait + std::fmt::Debug,
+    TEdge: Default + Copy + PartialOrd + Add<Output = TEdge> + std::fmt::Debug,
+    Ty: EdgeType,
+{
+    type Neighbors = Neighbors<'a, TNode, Ty>;
+
+    #[inline]
+    fn neighbors(self, node: TNode) -> Self::Neighbors {
+        GraphMap::neighbors(self, node)
+    }
+}
+
+impl<TNode, TEdge, Ty> NodeCount for GraphMap<TNode, TEdge, Ty>
+where
+    TNode: NodeTrait + std::fmt::Debug,
+    TEdge: Default + Copy + PartialOrd + Add<Output = TEdge> + std::fmt::Debug,
+    Ty: EdgeType,
+{
+    #[inline]
+    fn node_count(&self) -> usize {
+        GraphMap::node_count(self)
+    }
+}
+
+impl<TNode, TEdge, Ty> EdgeWeightLookup for GraphMap<TNode, TEdge, Ty>
+where
+    TNode: NodeTrait + std::fmt::Debug,
+    TEdge: Default + Copy + PartialOrd + Add<Output = TEdge> + std::fmt::Debug,
+    Ty: EdgeType,
+{
+    #[inline]
+    fn edge_weight(&self, a: TNode, b: TNode) -> Option<TEdge> {
+        self.get_edge(a, b).copied()
+    }
+}
+
+impl<TNode, TEdge, Ty> Visitable for GraphMap<TNode, TEdge, Ty>
+where
+    TNode: NodeTrait + std::fmt::Debug,
+    TEdge: Default + Copy + PartialOrd + Add<Output = TEdge> + std::fmt::Debug,
+    Ty: EdgeType,
+{
+    type Map = HashSet<TNode>;
+
+    fn visit_map(&self) -> HashSet<TNode> {
+        HashSet::with_capacity(GraphMap::node_count(self))
+    }
+
+    fn reset_map(&self, map: &mut HashSet<TNode>) {
+        map.clear();
+    }
+}
+
+/// Performs a breadth-first search on `graph`, starting from the given start node and
+/// searching for the given goal node.
+///
+/// See [`frontier_search`] for the details.
+pub fn bfs<G>(
+    graph: G,
+    start: G::NodeId,
+    goal: G::NodeId,
+) -> GraphSearchReport<G::NodeId, G::EdgeWeight>
+where
+    G: IntoNeighbors + EdgeWeightLookup + Visitable,
+    G::NodeId: NodeTrait,
+    G::EdgeWeight: Default + Copy + PartialOrd + Add<Output = G::EdgeWeight>,
+{
+    frontier_search(graph, start, goal, FrontierPolicy::Fifo, 0)
+}
+
+/// Performs a depth-first search on `graph`, starting from the given start node and
+/// searching for the given goal node.
+///
+/// See [`frontier_search`] for the details.
+pub fn dfs<G>(
+    graph: G,
+    start: G::NodeId,
+    goal: G::NodeId,
+) -> GraphSearchReport<G::NodeId, G::EdgeWeight>
+where
+    G: IntoNeighbors + EdgeWeightLookup + Visitable,
+    G::NodeId: NodeTrait,
+    G::EdgeWeight: Default + Copy + PartialOrd + Add<Output = G::EdgeWeight>,
+{
+    frontier_search(graph, start, goal, FrontierPolicy::Lifo, 0)
+}
+
+/// Performs a breadth-first search on `graph`, calling `visitor` on every event of the
+/// search.
+///
+/// See [`frontier_search_with`] for the details.
+pub fn bfs_with<G, V>(
+    graph: G,
+    start: G::NodeId,
+    goal: G::NodeId,
+    visitor: V,
+) -> GraphSearchReport<G::NodeId, G::EdgeWeight>
+where
+    G: IntoNeighbors + EdgeWeightLookup + Visitable,
+    G::NodeId: NodeTrait,
+    G::EdgeWeight: Default + Copy + PartialOrd + Add<Output = G::EdgeWeight>,
+    V: FnMut(SearchEvent<G::NodeId, G::EdgeWeight>) -> Control,
+{
+    frontier_search_with(graph, start, goal, FrontierPolicy::Fifo, 0, visitor)
+}
+
+/// Performs a depth-first search on `graph`, calling `visitor` on every event of the
+/// search.
+///
+/// See [`frontier_search_with`] for the details.
+pub fn dfs_with<G, V>(
+    graph: G,
+    start: G::NodeId,
+    goal: G::NodeId,
+    visitor: V,
+) -> GraphSearchReport<G::NodeId, G::EdgeWeight>
+where
+    G: IntoNeighbors + EdgeWeightLookup + Visitable,
+    G::NodeId: NodeTrait,
+    G::EdgeWeight: Default + Copy + PartialOrd + Add<Output = G::EdgeWeight>,
+    V: FnMut(SearchEvent<G::NodeId, G::EdgeWeight>) -> Control,
+{
+    frontier_search_with(graph, start, goal, FrontierPolicy::Lifo, 0, visitor)
+}
+
+/// Performs a graph search on `graph` where the next node to expand is chosen by `policy`.
+///
+/// This runs a [`FrontierSearch`] on the problem of reaching `goal` from `start`, keeping
+/// track of the seen nodes in the visit map of the graph.
+///
+/// # Arguments
+///
+/// * `graph` - The graph to search.
+/// * `start` - The starting node for the search.
+/// * `goal` - The goal node to search for.
+/// * `policy` - How the next node to expand is chosen from the frontier.
+/// * `seed` - The seed of the random number generator used by random policies.
+pub fn frontier_search<G>(
+    graph: G,
+    start: G::NodeId,
+    goal: G::NodeId,
+    policy: FrontierPolicy,
+    seed: u64,
+) -> GraphSearchReport<G::NodeId, G::EdgeWeight>
+where
+    G: IntoNeighbors + EdgeWeightLookup + Visitable,
+    G::NodeId: NodeTrait,
+    G::EdgeWeight: Default + Copy + PartialOrd + Add<Output = G::EdgeWeight>,
+{
+    frontier_search_with(graph, start, goal, policy, seed, |_| Control::Continue)
+}
+
+/// Performs the same search as [`frontier_search`], calling `visitor` on every event of
+/// the search.
+///
+/// See [`FrontierSearch::step_with`] for how the visitor controls the search.
+pub fn frontier_search_with<G, V>(
+    graph: G,
+    start: G::NodeId,
+    goal: G::NodeId,
+    policy: FrontierPolicy,
+    seed: u64,
+    visitor: V,
+) -> GraphSearchReport<G::NodeId, G::EdgeWeight>
+where
+    G: IntoNeighbors + EdgeWeightLookup + Visitable,
+    G::NodeId: NodeTrait,
+    G::EdgeWeight: Default + Copy + PartialOrd + Add<Output = G::EdgeWeight>,
+    V: FnMut(SearchEvent<G::NodeId, G::EdgeWeight>) -> Control,
+{
+    let problem = GraphProblem::new(graph, start, move |node| node == goal);
+    FrontierSearch::new(problem, policy, seed)
+        .with_visit_map(graph.visit_map())
+        .run_with(visitor)
+}